@@ -18,6 +18,7 @@ fn main() {
     let bindings = bindgen::Builder::default()
         .header("CRoaring/roaring.h")
         .generate_inline_functions(true)
+        .use_core()
         .allowlist_function("roaring.*")
         .allowlist_type("roaring.*")
         .allowlist_var("roaring.*")
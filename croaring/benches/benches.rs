@@ -2,7 +2,7 @@
 
 extern crate test;
 
-use croaring::Bitmap;
+use croaring::{Bitmap, Portable};
 use test::Bencher;
 
 #[bench]
@@ -42,6 +42,77 @@ fn bench_add_many(b: &mut Bencher) {
     });
 }
 
+#[bench]
+fn bench_add_spread_without_reserve(b: &mut Bencher) {
+    b.iter(|| {
+        let mut bitmap = Bitmap::create();
+        for i in 0..10_000 {
+            bitmap.add(i << 16);
+        }
+
+        bitmap
+    });
+}
+
+#[bench]
+fn bench_add_spread_with_reserve(b: &mut Bencher) {
+    b.iter(|| {
+        let mut bitmap = Bitmap::create();
+        bitmap.reserve(10_000);
+        for i in 0..10_000 {
+            bitmap.add(i << 16);
+        }
+
+        bitmap
+    });
+}
+
+#[bench]
+fn bench_add_many_1000000(b: &mut Bencher) {
+    let int_vec: Vec<u32> = (0..1_000_000).map(|i| i * 3).collect();
+
+    b.iter(|| {
+        let mut bitmap = Bitmap::create();
+        bitmap.add_many(&int_vec);
+
+        bitmap
+    });
+}
+
+#[bench]
+fn bench_add_loop_1000000(b: &mut Bencher) {
+    let int_vec: Vec<u32> = (0..1_000_000).map(|i| i * 3).collect();
+
+    b.iter(|| {
+        let mut bitmap = Bitmap::create();
+        for &i in &int_vec {
+            bitmap.add(i);
+        }
+
+        bitmap
+    });
+}
+
+#[bench]
+fn bench_extend_1000000(b: &mut Bencher) {
+    b.iter(|| {
+        let mut bitmap = Bitmap::create();
+        bitmap.extend((0..1_000_000).map(|i| i * 3));
+
+        bitmap
+    });
+}
+
+#[bench]
+fn bench_extend_sorted_1000000(b: &mut Bencher) {
+    b.iter(|| {
+        let mut bitmap = Bitmap::create();
+        bitmap.extend_sorted((0..1_000_000).map(|i| i * 3));
+
+        bitmap
+    });
+}
+
 #[bench]
 fn bench_remove(b: &mut Bencher) {
     let mut bitmap = Bitmap::create();
@@ -157,6 +228,64 @@ fn bench_or_inplace(b: &mut Bencher) {
     });
 }
 
+#[bench]
+fn bench_or_inplace_small_into_large(b: &mut Bencher) {
+    let small = Bitmap::of(&[500, 1000, 70_000]);
+    let large: Bitmap = (0..1_000_000).map(|i| i * 3).collect();
+
+    // The right hand side is a temporary in both benchmarks, which can only be
+    // reused by the owned form
+    b.iter(|| {
+        let mut bitmap = small.clone();
+        let other = large.clone();
+        bitmap.or_inplace(&other);
+
+        bitmap
+    });
+}
+
+#[bench]
+fn bench_or_inplace_owned_small_into_large(b: &mut Bencher) {
+    let small = Bitmap::of(&[500, 1000, 70_000]);
+    let large: Bitmap = (0..1_000_000).map(|i| i * 3).collect();
+
+    b.iter(|| {
+        let mut bitmap = small.clone();
+        let other = large.clone();
+        bitmap.or_inplace_owned(other);
+
+        bitmap
+    });
+}
+
+#[bench]
+fn bench_and_inplace_large_with_small(b: &mut Bencher) {
+    let small = Bitmap::of(&[500, 1000, 70_000]);
+    let large: Bitmap = (0..1_000_000).map(|i| i * 3).collect();
+
+    b.iter(|| {
+        let mut bitmap = large.clone();
+        let other = small.clone();
+        bitmap.and_inplace(&other);
+
+        bitmap
+    });
+}
+
+#[bench]
+fn bench_and_inplace_owned_large_with_small(b: &mut Bencher) {
+    let small = Bitmap::of(&[500, 1000, 70_000]);
+    let large: Bitmap = (0..1_000_000).map(|i| i * 3).collect();
+
+    b.iter(|| {
+        let mut bitmap = large.clone();
+        let other = small.clone();
+        bitmap.and_inplace_owned(other);
+
+        bitmap
+    });
+}
+
 #[bench]
 fn bench_fast_or(b: &mut Bencher) {
     let mut bitmap1 = Bitmap::create();
@@ -174,6 +303,83 @@ fn bench_fast_or(b: &mut Bencher) {
     });
 }
 
+fn many_small_bitmaps(count: u32) -> Vec<Bitmap> {
+    (0..count)
+        .map(|i| Bitmap::of(&[i, i * 7 + 1, i * 1_000 + 3, i * 70_000]))
+        .collect()
+}
+
+#[bench]
+fn bench_fast_or_1000_small(b: &mut Bencher) {
+    let bitmaps = many_small_bitmaps(1000);
+    let refs: Vec<&Bitmap> = bitmaps.iter().collect();
+
+    b.iter(|| {
+        Bitmap::fast_or(&refs);
+    });
+}
+
+#[bench]
+fn bench_or_inplace_fold_1000_small(b: &mut Bencher) {
+    let bitmaps = many_small_bitmaps(1000);
+
+    b.iter(|| {
+        let mut result = Bitmap::create();
+        for bitmap in &bitmaps {
+            result.or_inplace(bitmap);
+        }
+        result
+    });
+}
+
+#[bench]
+fn bench_fast_or_10_small(b: &mut Bencher) {
+    let bitmaps = many_small_bitmaps(10);
+    let refs: Vec<&Bitmap> = bitmaps.iter().collect();
+
+    b.iter(|| Bitmap::fast_or(&refs));
+}
+
+#[bench]
+fn bench_fast_or_heap_10_small(b: &mut Bencher) {
+    let bitmaps = many_small_bitmaps(10);
+    let refs: Vec<&Bitmap> = bitmaps.iter().collect();
+
+    b.iter(|| Bitmap::fast_or_heap(&refs));
+}
+
+#[bench]
+fn bench_fast_or_heap_1000_small(b: &mut Bencher) {
+    let bitmaps = many_small_bitmaps(1000);
+    let refs: Vec<&Bitmap> = bitmaps.iter().collect();
+
+    b.iter(|| Bitmap::fast_or_heap(&refs));
+}
+
+#[bench]
+fn bench_fast_or_50000_small(b: &mut Bencher) {
+    let bitmaps = many_small_bitmaps(50_000);
+    let refs: Vec<&Bitmap> = bitmaps.iter().collect();
+
+    b.iter(|| Bitmap::fast_or(&refs));
+}
+
+#[cfg(feature = "rayon")]
+#[bench]
+fn bench_par_fast_or_50000_small(b: &mut Bencher) {
+    let bitmaps = many_small_bitmaps(50_000);
+
+    b.iter(|| Bitmap::par_fast_or(&bitmaps));
+}
+
+#[bench]
+fn bench_fast_or_heap_50000_small(b: &mut Bencher) {
+    let bitmaps = many_small_bitmaps(50_000);
+    let refs: Vec<&Bitmap> = bitmaps.iter().collect();
+
+    b.iter(|| Bitmap::fast_or_heap(&refs));
+}
+
 #[bench]
 fn bench_fast_or_heap(b: &mut Bencher) {
     let mut bitmap1 = Bitmap::create();
@@ -238,7 +444,7 @@ fn bench_fast_xor(b: &mut Bencher) {
     bitmap2.add(35);
 
     b.iter(|| {
-        Bitmap::fast_or(&[&bitmap1, &bitmap2]);
+        Bitmap::fast_xor(&[&bitmap1, &bitmap2]);
     });
 }
 
@@ -311,6 +517,47 @@ fn bench_to_vec(b: &mut Bencher) {
     });
 }
 
+#[bench]
+fn bench_to_vec_1000000(b: &mut Bencher) {
+    let bitmap: Bitmap = (0..1_000_000).map(|i| i * 3).collect();
+
+    b.iter(|| bitmap.to_vec());
+}
+
+#[bench]
+fn bench_iter_collect_1000000(b: &mut Bencher) {
+    let bitmap: Bitmap = (0..1_000_000).map(|i| i * 3).collect();
+
+    b.iter(|| bitmap.iter().collect::<Vec<u32>>());
+}
+
+#[bench]
+fn bench_iter_collect_into_1000000(b: &mut Bencher) {
+    let bitmap: Bitmap = (0..1_000_000).map(|i| i * 3).collect();
+    let mut buffer = Vec::new();
+
+    b.iter(|| {
+        buffer.clear();
+        bitmap.iter().collect_into(&mut buffer);
+        buffer.len()
+    });
+}
+
+#[bench]
+fn bench_containers_10000(b: &mut Bencher) {
+    // One small container per high key, so splitting is dominated by the number of containers
+    let bitmap: Bitmap = (0..10_000u32).flat_map(|i| [i << 16, (i << 16) + 7]).collect();
+
+    b.iter(|| bitmap.containers().count());
+}
+
+#[bench]
+fn bench_containers_full(b: &mut Bencher) {
+    let bitmap = Bitmap::from_range(..);
+
+    b.iter(|| bitmap.containers().count());
+}
+
 #[bench]
 fn bench_get_serialized_size_in_bytes(b: &mut Bencher) {
     let mut bitmap = Bitmap::create();
@@ -333,6 +580,22 @@ fn bench_is_empty_true(b: &mut Bencher) {
     });
 }
 
+#[bench]
+fn bench_equals_1000000(b: &mut Bencher) {
+    let bitmap1: Bitmap = (0..1_000_000).map(|i| i * 3).collect();
+    let bitmap2 = bitmap1.clone();
+
+    b.iter(|| bitmap1.equals(&bitmap2));
+}
+
+#[bench]
+fn bench_iter_eq_1000000(b: &mut Bencher) {
+    let bitmap1: Bitmap = (0..1_000_000).map(|i| i * 3).collect();
+    let bitmap2 = bitmap1.clone();
+
+    b.iter(|| bitmap1.iter().eq(bitmap2.iter()));
+}
+
 #[bench]
 fn bench_is_empty_false(b: &mut Bencher) {
     let mut bitmap = Bitmap::create();
@@ -344,6 +607,30 @@ fn bench_is_empty_false(b: &mut Bencher) {
     });
 }
 
+#[bench]
+fn bench_clone_100000(b: &mut Bencher) {
+    let sources: [Bitmap; 2] = [(0..100_000).collect(), (50_000..150_000).step_by(3).collect()];
+    let mut bitmap = Bitmap::create();
+    let mut i = 0;
+
+    b.iter(|| {
+        bitmap = sources[i % 2].clone();
+        i += 1;
+    });
+}
+
+#[bench]
+fn bench_clone_from_100000(b: &mut Bencher) {
+    let sources: [Bitmap; 2] = [(0..100_000).collect(), (50_000..150_000).step_by(3).collect()];
+    let mut bitmap = Bitmap::create();
+    let mut i = 0;
+
+    b.iter(|| {
+        bitmap.clone_from(&sources[i % 2]);
+        i += 1;
+    });
+}
+
 #[bench]
 fn bench_of(b: &mut Bencher) {
     b.iter(|| Bitmap::of(&vec![10, 20, 30, 40]));
@@ -354,7 +641,7 @@ fn bench_serialize_100000(b: &mut Bencher) {
     let bitmap: Bitmap = (1..100000).collect();
 
     b.iter(|| {
-        bitmap.serialize();
+        bitmap.serialize::<Portable>();
     });
 }
 
@@ -363,26 +650,46 @@ fn bench_serialize_1000000(b: &mut Bencher) {
     let bitmap: Bitmap = (1..1000000).collect();
 
     b.iter(|| {
-        bitmap.serialize();
+        bitmap.serialize::<Portable>();
     });
 }
 
 #[bench]
 fn bench_deserialize_100000(b: &mut Bencher) {
     let bitmap: Bitmap = (1..100000).collect();
-    let serialized_buffer = bitmap.serialize();
+    let serialized_buffer = bitmap.serialize::<Portable>();
 
     b.iter(|| {
-        Bitmap::deserialize(&serialized_buffer);
+        Bitmap::deserialize::<Portable>(&serialized_buffer);
     });
 }
 
 #[bench]
 fn bench_deserialize_1000000(b: &mut Bencher) {
     let bitmap: Bitmap = (1..1000000).collect();
-    let serialized_buffer = bitmap.serialize();
+    let serialized_buffer = bitmap.serialize::<Portable>();
 
     b.iter(|| {
-        Bitmap::deserialize(&serialized_buffer);
+        Bitmap::deserialize::<Portable>(&serialized_buffer);
+    });
+}
+
+#[bench]
+fn bench_deserialize_unchecked_100000(b: &mut Bencher) {
+    let bitmap: Bitmap = (1..100000).collect();
+    let serialized_buffer = bitmap.serialize::<Portable>();
+
+    b.iter(|| unsafe {
+        Bitmap::deserialize_unchecked(&serialized_buffer);
+    });
+}
+
+#[bench]
+fn bench_deserialize_unchecked_1000000(b: &mut Bencher) {
+    let bitmap: Bitmap = (1..1000000).collect();
+    let serialized_buffer = bitmap.serialize::<Portable>();
+
+    b.iter(|| unsafe {
+        Bitmap::deserialize_unchecked(&serialized_buffer);
     });
 }
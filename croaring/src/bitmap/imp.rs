@@ -1,9 +1,16 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::mem;
+use core::ops::{Bound, RangeBounds};
 use ffi::roaring_bitmap_t;
-use std::convert::TryInto;
-use std::mem;
-use std::ops::{Bound, RangeBounds};
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
 
-use super::{Bitmap, Statistics};
+use super::serialization::{Deserializer, Frozen, Portable, Serializer};
+use super::{Bitmap, BitmapError, Statistics};
 
 impl Bitmap {
     #[inline]
@@ -42,6 +49,33 @@ impl Bitmap {
         Self::create_with_capacity(0)
     }
 
+    /// Returns a reference to a shared, always empty bitmap
+    ///
+    /// This is useful as a default argument, or anywhere an empty `&Bitmap` is
+    /// needed without allocating a new bitmap. The bitmap is created on first use,
+    /// and can be read from any number of threads.
+    ///
+    /// Requires the `std` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// fn allowed(extra: Option<&Bitmap>) -> Bitmap {
+    ///     Bitmap::of(&[1, 2, 3]).or(extra.unwrap_or(Bitmap::empty_ref()))
+    /// }
+    ///
+    /// assert_eq!(allowed(None), [1, 2, 3]);
+    /// assert_eq!(allowed(Some(&Bitmap::of(&[4]))), [1, 2, 3, 4]);
+    /// assert!(Bitmap::empty_ref().is_empty());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn empty_ref() -> &'static Bitmap {
+        static EMPTY: OnceLock<Bitmap> = OnceLock::new();
+        EMPTY.get_or_init(Self::create)
+    }
+
     /// Creates a new bitmap (initially empty) with a provided
     /// container-storage capacity (it is a performance hint).
     ///
@@ -56,17 +90,109 @@ impl Bitmap {
     /// ```
     #[inline]
     pub fn create_with_capacity(capacity: u32) -> Self {
+        Self::try_create_with_capacity(capacity).unwrap()
+    }
+
+    /// Creates a new bitmap (initially empty) with a provided container-storage
+    /// capacity, returning an error if the storage could not be allocated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::try_create_with_capacity(100_000).unwrap();
+    ///
+    /// assert!(bitmap.is_empty());
+    /// ```
+    #[inline]
+    pub fn try_create_with_capacity(capacity: u32) -> Result<Self, BitmapError> {
         let mut bitmap = mem::MaybeUninit::uninit();
         let success =
             unsafe { ffi::roaring_bitmap_init_with_capacity(bitmap.as_mut_ptr(), capacity) };
-        assert!(success);
+        if !success {
+            return Err(BitmapError::AllocationFailed);
+        }
 
-        Bitmap {
+        Ok(Bitmap {
             bitmap: unsafe { bitmap.assume_init() },
+        })
+    }
+
+    /// Creates a new bitmap (initially empty) with space for at least `capacity`
+    /// containers, the same as [`Bitmap::create_with_capacity`]
+    ///
+    /// Capacity is measured in containers: each distinct value of the high 16 bits
+    /// of the values in the bitmap needs one container, so a bitmap never needs more
+    /// than 65536. The capacity is only a hint, and never changes the bitmap's contents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// // Values spread over the first 100 containers
+    /// let mut bitmap = Bitmap::with_capacity(100);
+    /// bitmap.extend((0..100).map(|i| i << 16));
+    ///
+    /// assert_eq!(bitmap.cardinality(), 100);
+    /// ```
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::create_with_capacity(capacity.min(1 << 16) as u32)
+    }
+
+    /// Reserves space for at least `additional` more containers than the bitmap
+    /// currently has
+    ///
+    /// Capacity is measured in containers, see [`Bitmap::with_capacity`]. The bitmap's
+    /// contents are unchanged, and its existing containers are moved, not copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::of(&[1, 2, 3]);
+    /// bitmap.reserve(1000);
+    /// assert_eq!(bitmap.to_vec(), [1, 2, 3]);
+    ///
+    /// bitmap.extend((1..1000).map(|i| i << 16));
+    /// assert_eq!(bitmap.cardinality(), 1002);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let high_low_container = &self.bitmap.high_low_container;
+        let size = high_low_container.size as usize;
+        let required = size.saturating_add(additional).min(1 << 16);
+        if required <= high_low_container.allocation_size as usize {
+            return;
+        }
+
+        let mut result = Self::with_capacity(required);
+        unsafe {
+            // The new bitmap has no containers yet, so none can be shared
+            result.set_copy_on_write(self.get_copy_on_write());
+
+            // An empty bitmap may have no arrays at all, and there is nothing to move
+            if size > 0 {
+                // Move the containers into the larger arrays of the new bitmap, leaving
+                // this one empty, so dropping it only frees its arrays
+                let src = &mut self.bitmap.high_low_container;
+                let dst = &mut result.bitmap.high_low_container;
+                core::ptr::copy_nonoverlapping(src.containers, dst.containers, size);
+                core::ptr::copy_nonoverlapping(src.keys, dst.keys, size);
+                core::ptr::copy_nonoverlapping(src.typecodes, dst.typecodes, size);
+                dst.size = src.size;
+                src.size = 0;
+            }
         }
+        *self = result;
     }
 
-    /// Add the integer element to the bitmap
+    /// Add all the integer elements in the slice to the bitmap
+    ///
+    /// This is equivalent to calling `add` for every element, but faster,
+    /// especially when the elements are sorted.
     ///
     /// # Examples
     ///
@@ -80,6 +206,9 @@ impl Bitmap {
     /// assert!(bitmap.contains(1));
     /// assert!(bitmap.contains(2));
     /// assert!(bitmap.contains(3));
+    ///
+    /// bitmap.add_many(&[]);
+    /// assert_eq!(bitmap.cardinality(), 3);
     /// ```
     #[inline]
     pub fn add_many(&mut self, elements: &[u32]) {
@@ -93,6 +222,30 @@ impl Bitmap {
         }
     }
 
+    /// Add all the values from an iterator which yields them in ascending order
+    ///
+    /// Consecutive values which share a container are added without looking the
+    /// container up again, which is the fastest way to add a large sorted sequence.
+    /// Unsorted values are still added correctly, but more slowly than with
+    /// [`Extend::extend`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::of(&[5]);
+    /// bitmap.extend_sorted((0..1000).map(|i| i * 3));
+    ///
+    /// assert_eq!(bitmap.cardinality(), 1001);
+    /// assert!(bitmap.contains(5));
+    /// assert!(bitmap.contains(2997));
+    /// ```
+    #[inline]
+    pub fn extend_sorted<I: IntoIterator<Item = u32>>(&mut self, iter: I) {
+        self.add_many_from_iter(iter.into_iter())
+    }
+
     #[inline]
     pub fn add_many_from_iter<I>(&mut self, iter: I)
     where I: Iterator<Item = u32>
@@ -147,12 +300,79 @@ impl Bitmap {
     /// let mut bitmap = Bitmap::create();
     /// assert!(bitmap.add_checked(1));
     /// assert!(!bitmap.add_checked(1));
+    ///
+    /// bitmap.remove(1);
+    /// assert!(bitmap.add_checked(1));
+    /// assert_eq!(bitmap.cardinality(), 1);
     /// ```
     #[inline]
     pub fn add_checked(&mut self, element: u32) -> bool {
         unsafe { ffi::roaring_bitmap_add_checked(&mut self.bitmap, element) }
     }
 
+    /// Add the integer element to the bitmap. Returns true if the value was
+    /// already in the bitmap, the opposite of [`Bitmap::add_checked`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::create();
+    /// assert!(!bitmap.contains_then_add(1));
+    /// assert!(bitmap.contains_then_add(1));
+    /// assert_eq!(bitmap.cardinality(), 1);
+    /// ```
+    #[inline]
+    pub fn contains_then_add(&mut self, element: u32) -> bool {
+        !self.add_checked(element)
+    }
+
+    /// Add the integer element to the bitmap. Returns `Ok(true)` if the value was
+    /// added, `Ok(false)` if the value was already in the bitmap, the same as
+    /// [`Bitmap::add_checked`].
+    ///
+    /// The bundled CRoaring cannot report allocation failures when inserting
+    /// values, so this currently always returns `Ok`: an allocation failure is not
+    /// recoverable, as with [`Bitmap::add`]. Allocation failures are only reported
+    /// when creating a bitmap, see [`Bitmap::try_create_with_capacity`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::create();
+    /// assert_eq!(bitmap.try_add(1), Ok(true));
+    /// assert_eq!(bitmap.try_add(1), Ok(false));
+    /// ```
+    #[inline]
+    pub fn try_add(&mut self, element: u32) -> Result<bool, BitmapError> {
+        Ok(self.add_checked(element))
+    }
+
+    /// Add all the integer elements in the slice to the bitmap, the same as
+    /// [`Bitmap::add_many`]
+    ///
+    /// Like [`Bitmap::try_add`], this currently always returns `Ok`, since the bundled
+    /// CRoaring cannot report allocation failures when inserting values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::create();
+    /// bitmap.try_add_many(&[1, 2, 3]).unwrap();
+    ///
+    /// assert_eq!(bitmap, [1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn try_add_many(&mut self, elements: &[u32]) -> Result<(), BitmapError> {
+        self.add_many(elements);
+        Ok(())
+    }
+
     /// Add all values in range
     ///
     /// # Examples
@@ -193,8 +413,61 @@ impl Bitmap {
         }
     }
 
+    /// Add all values in range, returning the number of values which were not
+    /// already in the bitmap
+    ///
+    /// Bounds are interpreted the same way as in [`Bitmap::add_range`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::of(&[2, 3, 100]);
+    ///
+    /// assert_eq!(bitmap.insert_range_count(0..10), 8);
+    /// assert_eq!(bitmap.insert_range_count(0..10), 0);
+    /// assert_eq!(bitmap.insert_range_count(u32::MAX - 1..), 2);
+    /// assert_eq!(bitmap.insert_range_count(5..1), 0);
+    /// assert_eq!(bitmap.cardinality(), 13);
+    /// ```
+    #[inline]
+    pub fn insert_range_count<R: RangeBounds<u32>>(&mut self, range: R) -> u64 {
+        let (start, end) = range_to_inclusive(range);
+        let before = self.range_cardinality(start..=end);
+        self.add_range_closed(start, end);
+        self.range_cardinality(start..=end) - before
+    }
+
+    /// Add all values in range, returning the number of values which were not
+    /// already in the bitmap
+    ///
+    /// The range counterpart of [`Bitmap::add_checked`], and the same as
+    /// [`Bitmap::insert_range_count`]. Useful to keep an external count of values
+    /// up to date without computing the range cardinality at the call site.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::of(&[5, 6, 20]);
+    /// let mut total = bitmap.cardinality();
+    ///
+    /// total += bitmap.add_range_checked(0..10);
+    /// total += bitmap.add_range_checked(8..=20);
+    /// assert_eq!(total, 21);
+    /// assert_eq!(bitmap.cardinality(), total);
+    /// ```
+    #[inline]
+    pub fn add_range_checked<R: RangeBounds<u32>>(&mut self, range: R) -> u64 {
+        self.insert_range_count(range)
+    }
+
     /// Remove all values in range
     ///
+    /// Bounds are interpreted the same way as in [`Bitmap::add_range`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -212,6 +485,23 @@ impl Bitmap {
     ///
     /// bitmap.add_range(u32::MAX..=u32::MAX);
     /// assert!(bitmap.contains(u32::MAX));
+    ///
+    /// // Empty ranges do nothing
+    /// bitmap.remove_range(3..3);
+    /// bitmap.remove_range(4..1);
+    /// assert_eq!(bitmap.to_vec(), [3, u32::MAX]);
+    ///
+    /// // Ranges can span multiple containers
+    /// bitmap.add_range(0..300_000);
+    /// bitmap.remove_range(10..=200_000);
+    /// assert_eq!(bitmap.cardinality(), 10 + 99_999 + 1);
+    /// assert!(bitmap.contains(9));
+    /// assert!(!bitmap.contains(10));
+    /// assert!(!bitmap.contains(200_000));
+    /// assert!(bitmap.contains(200_001));
+    ///
+    /// bitmap.remove_range(0..=u32::MAX);
+    /// assert!(bitmap.is_empty());
     /// ```
     #[inline]
     pub fn remove_range<R: RangeBounds<u32>>(&mut self, range: R) {
@@ -221,8 +511,84 @@ impl Bitmap {
         }
     }
 
+    /// Add all values in `[start, end]`, including both `start` and `end`
+    ///
+    /// Equivalent to `add_range(start..=end)`, so `end` can be `u32::MAX` without
+    /// overflowing. Does nothing if `start > end`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::create();
+    /// bitmap.add_range_closed(1, 3);
+    /// assert_eq!(bitmap.to_vec(), [1, 2, 3]);
+    ///
+    /// bitmap.add_range_closed(5, 4);
+    /// assert_eq!(bitmap.cardinality(), 3);
+    ///
+    /// bitmap.add_range_closed(0, u32::MAX);
+    /// assert!(bitmap.is_full());
+    /// ```
+    #[inline]
+    pub fn add_range_closed(&mut self, start: u32, end: u32) {
+        unsafe { ffi::roaring_bitmap_add_range_closed(&mut self.bitmap, start, end) }
+    }
+
+    /// Remove all values in `[start, end]`, including both `start` and `end`
+    ///
+    /// Equivalent to `remove_range(start..=end)`, so `end` can be `u32::MAX` without
+    /// overflowing. Does nothing if `start > end`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::from_range(0..=u32::MAX);
+    /// bitmap.remove_range_closed(1, u32::MAX);
+    /// assert_eq!(bitmap.to_vec(), [0]);
+    ///
+    /// bitmap.remove_range_closed(1, 0);
+    /// assert_eq!(bitmap.to_vec(), [0]);
+    ///
+    /// bitmap.remove_range_closed(0, 0);
+    /// assert!(bitmap.is_empty());
+    /// ```
+    #[inline]
+    pub fn remove_range_closed(&mut self, start: u32, end: u32) {
+        unsafe { ffi::roaring_bitmap_remove_range_closed(&mut self.bitmap, start, end) }
+    }
+
+    /// Check whether all values in `[start, end]` are present, including both
+    /// `start` and `end`
+    ///
+    /// Equivalent to `contains_range(start..=end)`. An empty range (`start > end`)
+    /// is always contained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::from_range(u32::MAX - 10..=u32::MAX);
+    ///
+    /// assert!(bitmap.contains_range_closed(u32::MAX - 10, u32::MAX));
+    /// assert!(bitmap.contains_range_closed(u32::MAX, u32::MAX));
+    /// assert!(!bitmap.contains_range_closed(u32::MAX - 11, u32::MAX));
+    /// assert!(bitmap.contains_range_closed(10, 1));
+    /// ```
+    #[inline]
+    pub fn contains_range_closed(&self, start: u32, end: u32) -> bool {
+        self.contains_range(start..=end)
+    }
+
     /// Check whether a range of values of range are present
     ///
+    /// Bounds are interpreted the same way as in [`Bitmap::add_range`].
+    /// An empty range is always contained.
+    ///
     /// # Examples
     ///
     /// ```
@@ -230,6 +596,16 @@ impl Bitmap {
     ///
     /// let bitmap = Bitmap::of(&[1, 2]);
     /// assert!(bitmap.contains_range((1..3)));
+    /// assert!(bitmap.contains_range((1..=2)));
+    ///
+    /// // Partially present
+    /// assert!(!bitmap.contains_range((1..=3)));
+    /// // Entirely absent
+    /// assert!(!bitmap.contains_range((10..20)));
+    ///
+    /// // Empty ranges
+    /// assert!(bitmap.contains_range((10..10)));
+    /// assert!(bitmap.contains_range((20..10)));
     ///
     /// let mut bitmap = bitmap.clone();
     /// bitmap.add(u32::MAX - 1);
@@ -244,6 +620,9 @@ impl Bitmap {
 
     /// Empties the bitmap
     ///
+    /// The containers are released, but the bitmap itself can be reused without
+    /// creating a new one.
+    ///
     /// # Examples
     ///
     /// ```
@@ -255,12 +634,40 @@ impl Bitmap {
     /// bitmap.clear();
     ///
     /// assert!(bitmap.is_empty());
+    /// assert_eq!(bitmap.cardinality(), 0);
+    ///
+    /// bitmap.add(3);
+    /// assert_eq!(bitmap.to_vec(), [3]);
     /// ```
     #[inline]
     pub fn clear(&mut self) {
         unsafe { ffi::roaring_bitmap_clear(&mut self.bitmap) }
     }
 
+    /// Exchange the contents of this bitmap with `other`
+    ///
+    /// This is equivalent to [`std::mem::swap`], and runs in constant time: only
+    /// the bitmaps' headers are exchanged, their containers are not copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut current = Bitmap::of(&[1, 2]);
+    /// let mut next = Bitmap::from_range(100..200_000);
+    ///
+    /// current.swap(&mut next);
+    ///
+    /// assert_eq!(current, Bitmap::from_range(100..200_000));
+    /// assert_eq!(next.to_vec(), [1, 2]);
+    /// ```
+    #[inline]
+    pub fn swap(&mut self, other: &mut Bitmap) {
+        // roaring_bitmap_t doesn't store any pointers into itself, see `take_heap`
+        mem::swap(&mut self.bitmap, &mut other.bitmap);
+    }
+
     /// Clear the integer element from the bitmap
     ///
     /// # Examples
@@ -279,8 +686,8 @@ impl Bitmap {
         unsafe { ffi::roaring_bitmap_remove(&mut self.bitmap, element) }
     }
 
-    /// Remove the integer element from the bitmap. Returns true if a the value
-    /// was removed, false if the value was present in the bitmap.
+    /// Remove the integer element from the bitmap. Returns true if the value
+    /// was removed, false if the value was not present in the bitmap.
     ///
     /// # Examples
     ///
@@ -288,15 +695,59 @@ impl Bitmap {
     /// use croaring::Bitmap;
     ///
     /// let mut bitmap = Bitmap::create();
+    /// assert!(!bitmap.remove_checked(1));
     /// bitmap.add(1);
     /// assert!(bitmap.remove_checked(1));
     /// assert!(!bitmap.remove_checked(1));
+    /// assert!(bitmap.add_checked(1));
+    /// assert!(bitmap.remove_checked(1));
     /// ```
     #[inline]
     pub fn remove_checked(&mut self, element: u32) -> bool {
         unsafe { ffi::roaring_bitmap_remove_checked(&mut self.bitmap, element) }
     }
 
+    /// Retains only the elements for which `f` returns true, removing all
+    /// others from the bitmap. `f` is called once for each element, in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap: Bitmap = (1..10).chain([100_000, 100_001]).collect();
+    /// bitmap.retain(|value| value % 2 == 0);
+    ///
+    /// assert_eq!(bitmap.to_vec(), [2, 4, 6, 8, 100_000]);
+    /// ```
+    pub fn retain<F: FnMut(u32) -> bool>(&mut self, mut f: F) {
+        // Removing while iterating would invalidate the iterator, so the
+        // elements to remove are collected into a bitmap, which stays compressed,
+        // and then removed in one operation
+        let mut to_remove = Bitmap::create();
+        let mut buffer = [0u32; 256];
+        let mut rejected = [0u32; 256];
+        let mut iter = self.iter();
+        loop {
+            let n = iter.next_many(&mut buffer);
+            if n == 0 {
+                break;
+            }
+            let mut count = 0;
+            for &value in &buffer[..n] {
+                if !f(value) {
+                    rejected[count] = value;
+                    count += 1;
+                }
+            }
+            to_remove.add_many(&rejected[..count]);
+        }
+
+        if !to_remove.is_empty() {
+            self.andnot_inplace(&to_remove);
+        }
+    }
+
     /// Contains returns true if the integer element is contained in the bitmap
     ///
     /// # Examples
@@ -329,6 +780,10 @@ impl Bitmap {
     /// assert_eq!(shifted_up.to_vec(), [1, 2, 1001]);
     /// let big_shifted = bitmap1.add_offset(i64::from(u32::MAX) + 1);
     /// assert_eq!(big_shifted.to_vec(), []);
+    /// let big_shifted_down = bitmap1.add_offset(-i64::from(u32::MAX));
+    /// assert_eq!(big_shifted_down.to_vec(), [0]);
+    /// assert!(bitmap1.add_offset(i64::MIN).is_empty());
+    /// assert!(bitmap1.add_offset(i64::MAX).is_empty());
     /// ```
     #[inline]
     pub fn add_offset(&self, offset: i64) -> Self {
@@ -337,6 +792,10 @@ impl Bitmap {
 
     /// Returns number of elements in range
     ///
+    /// The range is normalized to a half-open range `[start, end)`: an excluded
+    /// start bound and an included end bound are each moved up by one, and unbounded
+    /// ends cover `0` and `u32::MAX` respectively. Empty ranges contain no elements.
+    ///
     /// # Examples
     ///
     /// ```
@@ -349,6 +808,17 @@ impl Bitmap {
     /// assert_eq!(bitmap.range_cardinality((2..5)), 2);
     /// assert_eq!(bitmap.range_cardinality((..5)), 3);
     /// assert_eq!(bitmap.range_cardinality((1..=4)), 3);
+    ///
+    /// // Empty ranges
+    /// assert_eq!(bitmap.range_cardinality((3..3)), 0);
+    /// assert_eq!(bitmap.range_cardinality((4..1)), 0);
+    ///
+    /// let bitmap = Bitmap::of(&[0, u32::MAX - 1, u32::MAX]);
+    ///
+    /// assert_eq!(bitmap.range_cardinality((u32::MAX - 1)..u32::MAX), 1);
+    /// assert_eq!(bitmap.range_cardinality((u32::MAX - 1)..=u32::MAX), 2);
+    /// assert_eq!(bitmap.range_cardinality(u32::MAX..), 1);
+    /// assert_eq!(bitmap.range_cardinality(..), 3);
     /// ```
     #[inline]
     pub fn range_cardinality<R: RangeBounds<u32>>(&self, range: R) -> u64 {
@@ -429,6 +899,33 @@ impl Bitmap {
         unsafe { ffi::roaring_bitmap_and_inplace(&mut self.bitmap, &other.bitmap) }
     }
 
+    /// Computes the intersection between two bitmaps and stores the result
+    /// in the current bitmap, consuming `other`
+    ///
+    /// Because `other` is no longer needed, the intersection is computed into
+    /// whichever bitmap has fewer containers, so fewer containers need to be visited
+    /// and freed. The benefit is small: intersections never copy containers, and
+    /// [`Bitmap::and_inplace`] is just as fast for bitmaps of similar size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap1 = Bitmap::of(&[15, 25]);
+    /// let bitmap2 = Bitmap::from_range(20..1_000_000);
+    ///
+    /// bitmap1.and_inplace_owned(bitmap2);
+    ///
+    /// assert_eq!(bitmap1.to_vec(), [25]);
+    /// ```
+    #[inline]
+    pub fn and_inplace_owned(&mut self, mut other: Self) {
+        // Swaps if `other` has fewer containers
+        other.swap_if_fewer_containers(self);
+        self.and_inplace(&other);
+    }
+
     /// Or computes the union between two bitmaps and returns the result
     /// as a new bitmap
     ///
@@ -473,6 +970,33 @@ impl Bitmap {
         unsafe { ffi::roaring_bitmap_or_inplace(&mut self.bitmap, &other.bitmap) }
     }
 
+    /// Computes the union between two bitmaps and stores the result in the
+    /// current bitmap, consuming `other`
+    ///
+    /// Because `other` is no longer needed, the union is computed into whichever
+    /// bitmap has more containers. Containers which are only present in that bitmap
+    /// are kept as they are, rather than copied, which makes this much faster than
+    /// [`Bitmap::or_inplace`] when the current bitmap is the smaller of the two.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap1 = Bitmap::of(&[15]);
+    /// let bitmap2 = Bitmap::from_range(1_000_000..2_000_000);
+    ///
+    /// bitmap1.or_inplace_owned(bitmap2);
+    ///
+    /// assert_eq!(bitmap1.cardinality(), 1_000_001);
+    /// assert!(bitmap1.contains(15));
+    /// ```
+    #[inline]
+    pub fn or_inplace_owned(&mut self, mut other: Self) {
+        self.swap_if_fewer_containers(&mut other);
+        self.or_inplace(&other);
+    }
+
     #[inline]
     pub fn lazy_or_inplace(&mut self, other: &Bitmap, force_bitsets: bool) -> &mut Self {
         unsafe {
@@ -494,9 +1018,24 @@ impl Bitmap {
         }
     }
 
+    /// Swaps with `other` if this bitmap has fewer containers
+    ///
+    /// Bitmaps with different copy on write settings are never swapped, as
+    /// the setting applies to the containers each bitmap already holds.
+    #[inline]
+    fn swap_if_fewer_containers(&mut self, other: &mut Bitmap) {
+        if self.bitmap.high_low_container.size < other.bitmap.high_low_container.size
+            && self.get_copy_on_write() == other.get_copy_on_write()
+        {
+            self.swap(other);
+        }
+    }
+
     /// Computes the union between many bitmaps quickly, as opposed to having
     /// to call or() repeatedly. Returns the result as a new bitmap.
     ///
+    /// The union of an empty slice is an empty bitmap.
+    ///
     /// # Examples
     ///
     /// ```
@@ -511,7 +1050,9 @@ impl Bitmap {
     /// assert_eq!(bitmap4.cardinality(), 3);
     /// assert!(bitmap4.contains(15));
     /// assert!(bitmap4.contains(25));
-    /// assert!(bitmap4.contains(25));
+    /// assert!(bitmap4.contains(35));
+    ///
+    /// assert!(Bitmap::fast_or(&[]).is_empty());
     /// ```
     #[inline]
     pub fn fast_or(bitmaps: &[&Bitmap]) -> Self {
@@ -531,6 +1072,12 @@ impl Bitmap {
     /// Compute the union of 'number' bitmaps using a heap. This can
     /// sometimes be faster than Bitmap::fast_or.
     ///
+    /// The heap repeatedly merges the two smallest bitmaps, so it tends to win
+    /// when unioning a very large number of bitmaps, or bitmaps of very different
+    /// sizes. [`Bitmap::fast_or`] is usually faster for a modest number of similar
+    /// bitmaps; compare both with the `bench_fast_or*` benchmarks for your workload.
+    /// The result is always the same as [`Bitmap::fast_or`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -545,7 +1092,9 @@ impl Bitmap {
     /// assert_eq!(bitmap4.cardinality(), 3);
     /// assert!(bitmap4.contains(15));
     /// assert!(bitmap4.contains(25));
-    /// assert!(bitmap4.contains(25));
+    /// assert!(bitmap4.contains(35));
+    ///
+    /// assert!(Bitmap::fast_or_heap(&[]).is_empty());
     /// ```
     #[inline]
     pub fn fast_or_heap(bitmaps: &[&Bitmap]) -> Self {
@@ -556,7 +1105,7 @@ impl Bitmap {
 
         unsafe {
             Self::take_heap(ffi::roaring_bitmap_or_many_heap(
-                bms.len() as u32,
+                bms.len().try_into().unwrap(),
                 bms.as_mut_ptr(),
             ))
         }
@@ -607,9 +1156,41 @@ impl Bitmap {
         unsafe { ffi::roaring_bitmap_xor_inplace(&mut self.bitmap, &other.bitmap) }
     }
 
+    /// Computes the symmetric difference between two bitmaps and stores the
+    /// result in the current bitmap, consuming `other`
+    ///
+    /// Like [`Bitmap::or_inplace_owned`], the result is computed into whichever bitmap
+    /// has more containers, so containers only present in that bitmap aren't copied.
+    ///
+    /// There is no owned form of [`Bitmap::andnot_inplace`]: a difference is not
+    /// symmetric, so the containers of `other` can never be reused in the result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap1 = Bitmap::of(&[15, 1_000_000]);
+    /// let bitmap2 = Bitmap::from_range(1_000_000..2_000_000);
+    ///
+    /// bitmap1.xor_inplace_owned(bitmap2);
+    ///
+    /// assert_eq!(bitmap1.cardinality(), 1_000_000);
+    /// assert!(bitmap1.contains(15));
+    /// assert!(!bitmap1.contains(1_000_000));
+    /// ```
+    #[inline]
+    pub fn xor_inplace_owned(&mut self, mut other: Self) {
+        self.swap_if_fewer_containers(&mut other);
+        self.xor_inplace(&other);
+    }
+
     /// Computes the symmetric difference (xor) between multiple bitmaps
     /// and returns new bitmap as a result.
     ///
+    /// The result contains the values present in an odd number of the bitmaps,
+    /// the same as folding `^` over them. The xor of an empty slice is an empty bitmap.
+    ///
     /// # Examples
     ///
     /// ```
@@ -624,6 +1205,8 @@ impl Bitmap {
     /// assert!(bitmap3.contains(15));
     /// assert!(!bitmap3.contains(25));
     /// assert!(bitmap3.contains(35));
+    ///
+    /// assert!(Bitmap::fast_xor(&[]).is_empty());
     /// ```
     #[inline]
     pub fn fast_xor(bitmaps: &[&Bitmap]) -> Self {
@@ -693,6 +1276,7 @@ impl Bitmap {
 
     /// Negates the bits in the given range
     /// any integer present in this range and in the bitmap is removed.
+    /// Values outside of the range are left untouched.
     /// Returns result as a new bitmap.
     ///
     /// # Examples
@@ -711,7 +1295,16 @@ impl Bitmap {
     /// assert!(bitmap2.contains(4));
     ///
     /// let bitmap3 = bitmap1.flip(1..=5);
-    /// assert_eq!(bitmap3.to_vec(), [1, 2, 3, 5])
+    /// assert_eq!(bitmap3.to_vec(), [1, 2, 3, 5]);
+    ///
+    /// // Flipping twice returns the original bitmap
+    /// assert_eq!(bitmap3.flip(1..=5), bitmap1);
+    ///
+    /// let bitmap4 = bitmap1.flip(..);
+    /// assert_eq!(bitmap4.cardinality(), u64::from(u32::MAX));
+    /// assert!(!bitmap4.contains(4));
+    /// assert!(bitmap4.contains(u32::MAX));
+    /// assert_eq!(bitmap4.flip(0..=u32::MAX), bitmap1);
     /// ```
     #[inline]
     pub fn flip<R: RangeBounds<u32>>(&self, range: R) -> Self {
@@ -738,6 +1331,11 @@ impl Bitmap {
     /// assert!(bitmap1.contains(4));
     /// bitmap1.flip_inplace(4..=4);
     /// assert_eq!(bitmap1.to_vec(), [1, 2]);
+    ///
+    /// bitmap1.flip_inplace(0..=u32::MAX);
+    /// assert_eq!(bitmap1.cardinality(), u64::from(u32::MAX) - 1);
+    /// bitmap1.flip_inplace(..);
+    /// assert_eq!(bitmap1.to_vec(), [1, 2]);
     /// ```
     #[inline]
     pub fn flip_inplace<R: RangeBounds<u32>>(&mut self, range: R) {
@@ -748,6 +1346,10 @@ impl Bitmap {
     /// Returns a vector containing all of the integers stored in the Bitmap
     /// in sorted order.
     ///
+    /// This copies the values in bulk, and is much faster than
+    /// `bitmap.iter().collect::<Vec<u32>>()`. To collect only part of a bitmap, use
+    /// [`BitmapIterator::collect_into`](crate::BitmapIterator::collect_into).
+    ///
     /// ```
     /// use croaring::Bitmap;
     ///
@@ -757,113 +1359,371 @@ impl Bitmap {
     /// ```
     #[inline]
     pub fn to_vec(&self) -> Vec<u32> {
+        let mut buffer = Vec::new();
+        self.to_vec_into(&mut buffer);
+        buffer
+    }
+
+    /// Clears `buffer` and fills it with all of the integers stored in the
+    /// Bitmap in sorted order.
+    ///
+    /// The buffer's existing capacity is reused, so a single buffer can be used
+    /// to export many bitmaps without reallocating each time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut buffer = Vec::with_capacity(10);
+    ///
+    /// Bitmap::of(&[15, 25]).to_vec_into(&mut buffer);
+    /// assert_eq!(buffer, [15, 25]);
+    ///
+    /// Bitmap::of(&[1, 2, 3]).to_vec_into(&mut buffer);
+    /// assert_eq!(buffer, [1, 2, 3]);
+    /// assert_eq!(buffer.capacity(), 10);
+    /// ```
+    #[inline]
+    pub fn to_vec_into(&self, buffer: &mut Vec<u32>) {
         let bitmap_size: usize = self.cardinality().try_into().unwrap();
 
-        let mut buffer: Vec<u32> = Vec::with_capacity(bitmap_size);
+        buffer.clear();
+        buffer.reserve(bitmap_size);
         unsafe {
             ffi::roaring_bitmap_to_uint32_array(&self.bitmap, buffer.as_mut_ptr());
             buffer.set_len(bitmap_size);
         }
-        buffer
     }
 
-    /// Computes the serialized size in bytes of the Bitmap.
-    #[inline]
-    pub fn get_serialized_size_in_bytes(&self) -> usize {
-        unsafe {
-            ffi::roaring_bitmap_portable_size_in_bytes(&self.bitmap)
-                .try_into()
-                .unwrap()
+    /// Returns the bitmap as a dense bitset of 64 bit words
+    ///
+    /// Value `i` is stored in word `i / 64`, as bit `i % 64` counting from the least
+    /// significant bit, i.e. it is in the bitmap iff `words[i / 64] & (1 << (i % 64)) != 0`.
+    /// The bitset has just enough words to hold [`Bitmap::maximum`], so an empty
+    /// bitmap returns an empty vector, and a bitmap containing `u32::MAX` returns
+    /// 2<sup>26</sup> words (512 MiB).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[0, 3, 64, 130]);
+    /// assert_eq!(bitmap.to_dense_bitset(), [0b1001, 0b1, 0b100]);
+    ///
+    /// assert!(Bitmap::create().to_dense_bitset().is_empty());
+    /// ```
+    pub fn to_dense_bitset(&self) -> Vec<u64> {
+        let len = match self.maximum() {
+            Some(maximum) => maximum as usize / 64 + 1,
+            None => return Vec::new(),
+        };
+        let mut words = vec![0; len];
+        for value in self.iter() {
+            words[value as usize / 64] |= 1 << (value % 64);
         }
+        words
     }
 
-    /// Serializes a bitmap to a slice of bytes.
+    /// Creates a bitmap from a dense bitset of 64 bit words
+    ///
+    /// Words and bits are ordered the same way as in [`Bitmap::to_dense_bitset`]:
+    /// bit `i % 64` of word `i / 64` (counting from the least significant bit) is
+    /// set iff value `i` is in the bitmap. Trailing zero words are allowed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `words` has more than 2<sup>26</sup> words, since values
+    /// beyond `u32::MAX` cannot be stored.
     ///
     /// # Examples
     ///
     /// ```
     /// use croaring::Bitmap;
     ///
+    /// let bitmap = Bitmap::from_dense_bitset(&[0b1001, 0b1, 0b100, 0]);
+    /// assert_eq!(bitmap, [0, 3, 64, 130]);
+    ///
+    /// assert_eq!(Bitmap::from_dense_bitset(&bitmap.to_dense_bitset()), bitmap);
+    /// ```
+    pub fn from_dense_bitset(words: &[u64]) -> Self {
+        assert!(words.len() <= 1 << 26, "a dense bitset can hold at most 2^26 words");
+        let mut result = Self::create();
+        result.extend_sorted(words.iter().enumerate().flat_map(|(i, &word)| {
+            let base = (i as u32) << 6;
+            let mut remaining = word;
+            core::iter::from_fn(move || {
+                if remaining == 0 {
+                    return None;
+                }
+                let bit = remaining.trailing_zeros();
+                remaining &= remaining - 1;
+                Some(base | bit)
+            })
+        }));
+        result
+    }
+
+    /// Computes the serialized size in bytes of the Bitmap in the [`Portable`] format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, Portable};
+    ///
+    /// let bitmap: Bitmap = (1..5).collect();
+    ///
+    /// assert_eq!(bitmap.get_serialized_size_in_bytes(), bitmap.serialize::<Portable>().len());
+    /// ```
+    #[inline]
+    pub fn get_serialized_size_in_bytes(&self) -> usize {
+        Portable::get_serialized_size_in_bytes(self)
+    }
+
+    /// Computes the serialized size in bytes of the Bitmap in the [`Frozen`] format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, Frozen};
+    ///
+    /// let bitmap: Bitmap = (1..5).collect();
+    ///
+    /// assert_eq!(bitmap.get_frozen_serialized_size_in_bytes(), bitmap.serialize::<Frozen>().len());
+    /// ```
+    #[inline]
+    pub fn get_frozen_serialized_size_in_bytes(&self) -> usize {
+        Frozen::get_serialized_size_in_bytes(self)
+    }
+
+    /// Serializes a bitmap to a slice of bytes in format `S`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, Frozen, Native, Portable};
+    ///
     /// let original_bitmap: Bitmap = (1..5).collect();
     ///
-    /// let serialized_buffer = original_bitmap.serialize();
+    /// let serialized_buffer = original_bitmap.serialize::<Portable>();
+    /// let deserialized_bitmap = Bitmap::deserialize::<Portable>(&serialized_buffer);
+    /// assert_eq!(original_bitmap, deserialized_bitmap);
     ///
-    /// let deserialized_bitmap = Bitmap::deserialize(&serialized_buffer);
+    /// let serialized_buffer = original_bitmap.serialize::<Native>();
+    /// let deserialized_bitmap = Bitmap::deserialize::<Native>(&serialized_buffer);
+    /// assert_eq!(original_bitmap, deserialized_bitmap);
     ///
+    /// let serialized_buffer = original_bitmap.serialize::<Frozen>();
+    /// let deserialized_bitmap = Bitmap::deserialize::<Frozen>(&serialized_buffer);
     /// assert_eq!(original_bitmap, deserialized_bitmap);
     /// ```
     #[inline]
-    pub fn serialize(&self) -> Vec<u8> {
-        let capacity = self.get_serialized_size_in_bytes();
-        let mut dst = Vec::with_capacity(capacity);
+    pub fn serialize<S: Serializer>(&self) -> Vec<u8> {
+        let mut dst = Vec::new();
+        S::serialize_into(self, &mut dst);
+        dst
+    }
 
-        unsafe {
-            ffi::roaring_bitmap_portable_serialize(
-                &self.bitmap,
-                dst.as_mut_ptr() as *mut ::libc::c_char,
-            );
-            dst.set_len(capacity);
-        }
+    /// Serializes a bitmap in format `S` into `buffer`, and returns the serialized bytes.
+    ///
+    /// The buffer is cleared first and grown as needed, so reusing one buffer avoids
+    /// an allocation per call when serializing many bitmaps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, Portable};
+    ///
+    /// let mut buffer = Vec::new();
+    ///
+    /// for bitmap in [Bitmap::of(&[1, 2, 3]), (1..100).collect()] {
+    ///     let serialized = bitmap.serialize_into::<Portable>(&mut buffer);
+    ///     assert_eq!(serialized, bitmap.serialize::<Portable>());
+    ///     assert_eq!(Bitmap::deserialize::<Portable>(serialized), bitmap);
+    /// }
+    /// ```
+    #[inline]
+    pub fn serialize_into<'a, S: Serializer>(&self, buffer: &'a mut Vec<u8>) -> &'a [u8] {
+        buffer.clear();
+        S::serialize_into(self, buffer);
+        buffer
+    }
 
-        dst
+    /// Serializes a bitmap in the [`Frozen`] format into `buffer`, and returns the
+    /// serialized bytes, aligned to 32 bytes.
+    ///
+    /// The buffer is cleared first, then padded with zeros so the serialized bytes
+    /// start on a 32 byte boundary. The returned slice can be passed directly to
+    /// [`FrozenBitmapView`](crate::FrozenBitmapView) without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, FrozenBitmapView};
+    ///
+    /// let bitmap: Bitmap = (1..5).chain(100_000..200_000).collect();
+    ///
+    /// let mut buffer = Vec::new();
+    /// let serialized = bitmap.serialize_frozen_into(&mut buffer);
+    /// assert_eq!(serialized.as_ptr().align_offset(32), 0);
+    /// assert_eq!(serialized.len(), bitmap.get_frozen_serialized_size_in_bytes());
+    ///
+    /// let view = FrozenBitmapView::try_from(serialized).unwrap();
+    /// assert_eq!(*view, bitmap);
+    /// ```
+    pub fn serialize_frozen_into<'a>(&self, buffer: &'a mut Vec<u8>) -> &'a [u8] {
+        buffer.clear();
+        // Reserve room for the padding up front, so serializing can't reallocate
+        // the buffer and move the serialized bytes off the boundary
+        buffer.reserve(self.get_frozen_serialized_size_in_bytes() + 31);
+        let offset = buffer.as_ptr().align_offset(32);
+        buffer.resize(offset, 0);
+        Frozen::serialize_into(self, buffer);
+        &buffer[offset..]
     }
 
-    /// Given a serialized bitmap as slice of bytes returns a bitmap instance.
+    /// Serializes a bitmap in the [`Portable`] format, writing the bytes to `writer`.
+    ///
+    /// Returns the number of bytes written, which is always
+    /// [`Bitmap::get_serialized_size_in_bytes`].
+    ///
+    /// Requires the `std` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, Portable};
+    ///
+    /// let bitmap: Bitmap = (1..5).collect();
+    ///
+    /// let mut writer: Vec<u8> = Vec::new();
+    /// let written = bitmap.serialize_into_writer(&mut writer).unwrap();
+    ///
+    /// assert_eq!(written, writer.len());
+    /// assert_eq!(Bitmap::deserialize::<Portable>(&writer), bitmap);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn serialize_into_writer<W: Write>(&self, writer: &mut W) -> io::Result<usize> {
+        let buffer = self.serialize::<Portable>();
+        writer.write_all(&buffer)?;
+        Ok(buffer.len())
+    }
+
+    /// Given a serialized bitmap in format `D` as slice of bytes returns a bitmap instance.
     /// See example of #serialize function.
     ///
-    /// On invalid input returns None.
+    /// On invalid input returns None, including input serialized in a different format.
     ///
     /// # Examples
     ///
     /// ```
-    /// use croaring::Bitmap;
+    /// use croaring::{Bitmap, Native, Portable};
     ///
     /// let original_bitmap: Bitmap = (1..5).collect();
-    /// let serialized_buffer = original_bitmap.serialize();
+    /// let serialized_buffer = original_bitmap.serialize::<Portable>();
     ///
-    /// let deserialized_bitmap = Bitmap::try_deserialize(&serialized_buffer);
+    /// let deserialized_bitmap = Bitmap::try_deserialize::<Portable>(&serialized_buffer);
     /// assert_eq!(original_bitmap, deserialized_bitmap.unwrap());
     ///
+    /// let deserialized_bitmap = Bitmap::try_deserialize::<Native>(&serialized_buffer);
+    /// assert!(deserialized_bitmap.is_none());
+    ///
     /// let invalid_buffer: Vec<u8> = vec![3];
-    /// let deserialized_bitmap = Bitmap::try_deserialize(&invalid_buffer);
+    /// let deserialized_bitmap = Bitmap::try_deserialize::<Portable>(&invalid_buffer);
     /// assert!(deserialized_bitmap.is_none());
     /// ```
     #[inline]
-    pub fn try_deserialize(buffer: &[u8]) -> Option<Self> {
-        unsafe {
-            let bitmap = ffi::roaring_bitmap_portable_deserialize_safe(
-                buffer.as_ptr() as *const ::libc::c_char,
-                buffer.len().try_into().unwrap(),
-            );
-
-            if !bitmap.is_null() {
-                Some(Self::take_heap(bitmap))
-            } else {
-                None
-            }
-        }
+    pub fn try_deserialize<D: Deserializer>(buffer: &[u8]) -> Option<Self> {
+        D::try_deserialize(buffer)
     }
 
-    /// Given a serialized bitmap as slice of bytes returns a bitmap instance.
+    /// Given a serialized bitmap in format `D` as slice of bytes returns a bitmap instance.
     /// See example of #serialize function.
     ///
     /// On invalid input returns empty bitmap.
     #[inline]
-    pub fn deserialize(buffer: &[u8]) -> Self {
-        Self::try_deserialize(buffer).unwrap_or_else(Bitmap::create)
+    pub fn deserialize<D: Deserializer>(buffer: &[u8]) -> Self {
+        Self::try_deserialize::<D>(buffer).unwrap_or_else(Bitmap::create)
     }
 
-    /// Given a serialized bitmap as slice of bytes returns the cardinality.
+    /// Reads a bitmap in the [`Portable`] format from `reader`, as written by
+    /// [`Bitmap::serialize_into_writer`].
     ///
-    /// On invalid input returns None.
+    /// Exactly the bytes of one bitmap are consumed, so several bitmaps can be read
+    /// back to back from the same stream. Truncated or invalid input results in an error.
+    ///
+    /// Requires the `std` feature.
     ///
     /// # Examples
     ///
     /// ```
+    /// use std::io::{Cursor, ErrorKind};
     /// use croaring::Bitmap;
     ///
+    /// let bitmap1: Bitmap = (1..5).collect();
+    /// let bitmap2 = Bitmap::of(&[100, 100_000]);
+    ///
+    /// let mut buffer: Vec<u8> = Vec::new();
+    /// bitmap1.serialize_into_writer(&mut buffer).unwrap();
+    /// bitmap2.serialize_into_writer(&mut buffer).unwrap();
+    ///
+    /// let mut reader = Cursor::new(&buffer);
+    /// assert_eq!(Bitmap::deserialize_from_reader(&mut reader).unwrap(), bitmap1);
+    /// assert_eq!(Bitmap::deserialize_from_reader(&mut reader).unwrap(), bitmap2);
+    ///
+    /// let err = Bitmap::deserialize_from_reader(&mut reader).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn deserialize_from_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let buffer = super::serialization::read_portable(reader)?;
+        Self::try_deserialize::<Portable>(&buffer)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid serialized bitmap"))
+    }
+
+    /// Given a serialized bitmap in the [`Portable`] format as slice of bytes returns
+    /// a bitmap instance, without validating the input.
+    ///
+    /// This skips the bounds checks done by [`Bitmap::deserialize`], which is faster
+    /// for input from a trusted producer, see the `bench_deserialize_unchecked_*` benchmarks.
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must contain a bitmap serialized in the [`Portable`] format, e.g. by
+    /// `serialize::<Portable>()`. Passing truncated or otherwise malformed bytes
+    /// is undefined behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, Portable};
+    ///
+    /// let original_bitmap: Bitmap = (1..5).collect();
+    /// let serialized_buffer = original_bitmap.serialize::<Portable>();
+    ///
+    /// let deserialized_bitmap = unsafe { Bitmap::deserialize_unchecked(&serialized_buffer) };
+    ///
+    /// assert_eq!(original_bitmap, deserialized_bitmap);
+    /// ```
+    #[inline]
+    pub unsafe fn deserialize_unchecked(buffer: &[u8]) -> Self {
+        let bitmap = ffi::roaring_bitmap_portable_deserialize(buffer.as_ptr() as *const ::libc::c_char);
+        Self::take_heap(bitmap)
+    }
+
+    /// Given a serialized bitmap in the [`Portable`] format as slice of bytes returns
+    /// the cardinality.
+    ///
+    /// On invalid input returns None.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, Portable};
+    ///
     /// let original_bitmap: Bitmap = (1..5).collect();
-    /// let serialized_buffer = original_bitmap.serialize();
+    /// let serialized_buffer = original_bitmap.serialize::<Portable>();
     ///
     /// let deserialized_bitmap = Bitmap::try_deserialize_cardinality(&serialized_buffer);
     /// assert_eq!(original_bitmap.cardinality(), deserialized_bitmap.unwrap());
@@ -931,6 +1791,33 @@ impl Bitmap {
         }
     }
 
+    /// Creates a new bitmap from a strictly ascending slice of u32 integers
+    ///
+    /// Returns None if the slice is not sorted, or contains duplicates. Sorted
+    /// input lets the bitmap be built one container at a time, without searching
+    /// for the container each element belongs in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::from_sorted_slice(&[1, 2, 100_000]).unwrap();
+    /// assert_eq!(bitmap.to_vec(), [1, 2, 100_000]);
+    ///
+    /// assert!(Bitmap::from_sorted_slice(&[]).unwrap().is_empty());
+    /// assert!(Bitmap::from_sorted_slice(&[2, 1]).is_none());
+    /// assert!(Bitmap::from_sorted_slice(&[1, 1, 2]).is_none());
+    /// ```
+    #[inline]
+    pub fn from_sorted_slice(elements: &[u32]) -> Option<Self> {
+        if elements.windows(2).all(|pair| pair[0] < pair[1]) {
+            Some(Self::of(elements))
+        } else {
+            None
+        }
+    }
+
     #[inline]
     pub fn of_using_bitsets(elements: &[u32]) -> Self {
         unsafe {
@@ -978,6 +1865,13 @@ impl Bitmap {
     /// let bitmap = Bitmap::from_range_with_step(0..10, 3);
     /// assert_eq!(bitmap.to_vec(), [0, 3, 6, 9]);
     ///
+    /// // The upper bound is included if it is a multiple of step away
+    /// let bitmap = Bitmap::from_range_with_step(0..=9, 3);
+    /// assert_eq!(bitmap.to_vec(), [0, 3, 6, 9]);
+    ///
+    /// // A step of one is the same as `from_range`
+    /// assert_eq!(Bitmap::from_range_with_step(5..100, 1), Bitmap::from_range(5..100));
+    ///
     /// // empty ranges
     /// assert_eq!(Bitmap::from_range_with_step(0..0, 1), Bitmap::create());
     /// assert_eq!(Bitmap::from_range_with_step(100..=0, 1), Bitmap::create());
@@ -1019,6 +1913,49 @@ impl Bitmap {
         }
     }
 
+    /// Creates a new bitmap with all values in `range`, and compresses it with
+    /// [`Bitmap::run_optimize`]
+    ///
+    /// Bounds are interpreted the same way as in [`Bitmap::from_range`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::from_range_optimized(5..100_000);
+    ///
+    /// assert_eq!(bitmap, Bitmap::from_range(5..100_000));
+    /// assert_eq!(bitmap.statistics().n_run_containers, 2);
+    /// ```
+    #[inline]
+    pub fn from_range_optimized<R: RangeBounds<u32>>(range: R) -> Self {
+        let mut bitmap = Self::from_range(range);
+        bitmap.run_optimize();
+        bitmap
+    }
+
+    /// Creates a new bitmap from a slice of u32 integers, and compresses it with
+    /// [`Bitmap::run_optimize`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let elements: Vec<u32> = (1..1000).chain(2000..3000).collect();
+    /// let bitmap = Bitmap::of_optimized(&elements);
+    ///
+    /// assert_eq!(bitmap, Bitmap::of(&elements));
+    /// assert!(bitmap.get_serialized_size_in_bytes() < Bitmap::of(&elements).get_serialized_size_in_bytes());
+    /// ```
+    #[inline]
+    pub fn of_optimized(elements: &[u32]) -> Self {
+        let mut bitmap = Self::of(elements);
+        bitmap.run_optimize();
+        bitmap
+    }
+
     /// Shrink the memory allocation of the bitmap if needed
     ///
     /// Returns the number of bytes saved
@@ -1033,13 +1970,16 @@ impl Bitmap {
     /// assert!(saved_bytes > 0);
     /// let more_saved_bytes = bitmap.shrink_to_fit();
     /// assert_eq!(more_saved_bytes, 0);
+    /// ```
     #[inline]
     pub fn shrink_to_fit(&mut self) -> usize {
         let result = unsafe { ffi::roaring_bitmap_shrink_to_fit(&mut self.bitmap) };
         result as usize
     }
 
-    /// Compresses of the bitmap. Returns true if the bitmap was modified.
+    /// Compresses of the bitmap by converting containers to run containers
+    /// where it is more space efficient. Returns true if the result has at least
+    /// one run container.
     ///
     /// # Examples
     ///
@@ -1071,16 +2011,77 @@ impl Bitmap {
     ///
     /// assert_eq!(bitmap.cardinality(), 900);
     ///
+    /// let old_size = bitmap.get_serialized_size_in_bytes();
     /// bitmap.run_optimize();
     ///
     /// assert!(bitmap.remove_run_compression());
     /// assert!(!bitmap.remove_run_compression());
+    /// assert_eq!(bitmap.get_serialized_size_in_bytes(), old_size);
     /// ```
     #[inline]
     pub fn remove_run_compression(&mut self) -> bool {
         unsafe { ffi::roaring_bitmap_remove_run_compression(&mut self.bitmap) }
     }
 
+    /// Returns true if copy-on-write is enabled for this bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::create();
+    /// assert!(!bitmap.get_copy_on_write());
+    /// ```
+    #[inline]
+    pub fn get_copy_on_write(&self) -> bool {
+        unsafe { ffi::roaring_bitmap_get_copy_on_write(&self.bitmap) }
+    }
+
+    /// Enables or disables copy-on-write for this bitmap.
+    ///
+    /// When copy-on-write is enabled, cloning the bitmap (or using it as the
+    /// source of an operation which copies containers) shares the underlying
+    /// containers between the bitmaps instead of copying them eagerly. A shared
+    /// container is only copied when one of the bitmaps sharing it is modified.
+    ///
+    /// Note that cloning a copy-on-write bitmap modifies the internal
+    /// representation of the source bitmap as well, to mark its containers as
+    /// shared.
+    ///
+    /// # Safety
+    ///
+    /// Containers shared by copy-on-write are reference counted without any
+    /// synchronization. While copy-on-write is enabled, bitmaps which may share
+    /// containers (a bitmap, its clones, and the results of operations on them)
+    /// must not be accessed concurrently from multiple threads, even through
+    /// shared references.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap: Bitmap = (0..100_000).collect();
+    /// unsafe { bitmap.set_copy_on_write(true) };
+    /// assert!(bitmap.get_copy_on_write());
+    ///
+    /// let mut copy = bitmap.clone();
+    /// assert!(copy.get_copy_on_write());
+    ///
+    /// copy.add(200_000);
+    /// copy.remove(5);
+    ///
+    /// assert_eq!(bitmap.cardinality(), 100_000);
+    /// assert!(bitmap.contains(5));
+    /// assert!(!bitmap.contains(200_000));
+    /// assert_eq!(copy.cardinality(), 100_000);
+    /// ```
+    #[inline]
+    pub unsafe fn set_copy_on_write(&mut self, enabled: bool) {
+        ffi::roaring_bitmap_set_copy_on_write(&mut self.bitmap, enabled)
+    }
+
     /// Returns true if the Bitmap is empty.
     /// Faster than doing: bitmap.cardinality() == 0)
     ///
@@ -1102,6 +2103,53 @@ impl Bitmap {
         unsafe { ffi::roaring_bitmap_is_empty(&self.bitmap) }
     }
 
+    /// Returns true if the Bitmap contains every u32 value.
+    /// Equivalent to: bitmap.cardinality() == 1 << 32
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::create();
+    /// bitmap.flip_inplace(..);
+    ///
+    /// assert!(bitmap.is_full());
+    ///
+    /// bitmap.remove(u32::MAX);
+    ///
+    /// assert!(!bitmap.is_full());
+    /// ```
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.cardinality() == 1 << 32
+    }
+
+    /// Return true if Self and &other contain exactly the same values, the same as `==`
+    ///
+    /// The comparison is done container by container, so it is much faster than
+    /// comparing the values one at a time. Bitmaps are equal regardless of how
+    /// their containers are stored (e.g. after [`Bitmap::run_optimize`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap1: Bitmap = (1..100_000).collect();
+    /// let mut bitmap2 = bitmap1.clone();
+    /// bitmap2.run_optimize();
+    ///
+    /// assert!(bitmap1.equals(&bitmap2));
+    ///
+    /// bitmap2.remove(50_000);
+    /// assert!(!bitmap1.equals(&bitmap2));
+    /// ```
+    #[inline]
+    pub fn equals(&self, other: &Self) -> bool {
+        unsafe { ffi::roaring_bitmap_equals(&self.bitmap, &other.bitmap) }
+    }
+
     /// Return true if all the elements of Self are in &other.
     ///
     /// # Examples
@@ -1117,6 +2165,14 @@ impl Bitmap {
     /// assert!(bitmap2.is_subset(&bitmap1));
     /// assert!(bitmap3.is_subset(&bitmap1));
     /// assert!(!bitmap4.is_subset(&bitmap1));
+    ///
+    /// // A bitmap is always a subset of itself
+    /// assert!(bitmap1.is_subset(&bitmap1));
+    ///
+    /// // The empty bitmap is a subset of every bitmap
+    /// let empty_bitmap = Bitmap::create();
+    /// assert!(empty_bitmap.is_subset(&bitmap1));
+    /// assert!(empty_bitmap.is_subset(&empty_bitmap));
     /// ```
     #[inline]
     pub fn is_subset(&self, other: &Self) -> bool {
@@ -1136,9 +2192,16 @@ impl Bitmap {
     /// let bitmap3: Bitmap = (5..10).collect();
     /// let bitmap4: Bitmap = (9..11).collect();
     ///
-    /// assert!(bitmap2.is_subset(&bitmap1));
-    /// assert!(!bitmap3.is_subset(&bitmap1));
-    /// assert!(!bitmap4.is_subset(&bitmap1));
+    /// assert!(bitmap2.is_strict_subset(&bitmap1));
+    /// assert!(!bitmap3.is_strict_subset(&bitmap1));
+    /// assert!(!bitmap4.is_strict_subset(&bitmap1));
+    ///
+    /// // A bitmap is never a strict subset of itself
+    /// assert!(!bitmap1.is_strict_subset(&bitmap1));
+    ///
+    /// let empty_bitmap = Bitmap::create();
+    /// assert!(empty_bitmap.is_strict_subset(&bitmap1));
+    /// assert!(!empty_bitmap.is_strict_subset(&empty_bitmap));
     /// ```
     #[inline]
     pub fn is_strict_subset(&self, other: &Self) -> bool {
@@ -1147,6 +2210,8 @@ impl Bitmap {
 
     /// Return true if Self and &other intersect
     ///
+    /// This is faster than computing the intersection and checking if it is empty.
+    ///
     /// # Examples
     ///
     /// ```
@@ -1159,6 +2224,10 @@ impl Bitmap {
     /// assert_eq!(bitmap1.intersect(&bitmap2), false);
     /// assert_eq!(bitmap1.intersect(&bitmap3), true);
     /// assert_eq!(bitmap2.intersect(&bitmap3), true);
+    ///
+    /// let empty_bitmap = Bitmap::create();
+    /// assert_eq!(bitmap1.intersect(&empty_bitmap), false);
+    /// assert_eq!(empty_bitmap.intersect(&empty_bitmap), false);
     /// ```
     #[inline]
     pub fn intersect(&self, other: &Self) -> bool {
@@ -1188,8 +2257,85 @@ impl Bitmap {
         unsafe { ffi::roaring_bitmap_intersect_with_range(&self.bitmap, start, end) }
     }
 
+    /// Removes all values outside `range`, so only values within it remain
+    ///
+    /// Equivalent to `bitmap &= &Bitmap::from_range(range)`, without creating a
+    /// temporary bitmap. An empty range clears the bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::of(&[1, 100, 101, 100_000, u32::MAX]);
+    ///
+    /// bitmap.intersect_with_range_inplace(100..=100_000);
+    /// assert_eq!(bitmap.to_vec(), [100, 101, 100_000]);
+    ///
+    /// bitmap.intersect_with_range_inplace(101..);
+    /// assert_eq!(bitmap.to_vec(), [101, 100_000]);
+    ///
+    /// bitmap.intersect_with_range_inplace(5..5);
+    /// assert!(bitmap.is_empty());
+    /// ```
+    pub fn intersect_with_range_inplace<R: RangeBounds<u32>>(&mut self, range: R) {
+        let (start, end) = range_to_exclusive(range);
+        if start >= end {
+            self.clear();
+            return;
+        }
+
+        // A non-empty range must start within the u32 domain
+        self.remove_range(..start as u32);
+        if let Ok(end) = u32::try_from(end) {
+            self.remove_range(end..);
+        }
+    }
+
+    /// Splits the bitmap into the values less than `pivot` and the values greater
+    /// than or equal to `pivot`, returned as new bitmaps in that order
+    ///
+    /// The two bitmaps are disjoint, and their union is the original bitmap. When
+    /// every value falls on one side, that side is a plain copy of the bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 5, 10, 100_000]);
+    ///
+    /// let (lower, upper) = bitmap.split_at(10);
+    /// assert_eq!(lower, [1, 5]);
+    /// assert_eq!(upper, [10, 100_000]);
+    ///
+    /// let (lower, upper) = bitmap.split_at(0);
+    /// assert!(lower.is_empty());
+    /// assert_eq!(upper, bitmap);
+    /// ```
+    pub fn split_at(&self, pivot: u32) -> (Self, Self) {
+        let lower_cardinality = self.cardinality_lt(pivot);
+        if lower_cardinality == 0 {
+            return (Self::create(), self.clone());
+        }
+        if lower_cardinality == self.cardinality() {
+            return (self.clone(), Self::create());
+        }
+
+        let mut lower = self.clone();
+        lower.remove_range(pivot..);
+        let mut upper = self.clone();
+        upper.remove_range(..pivot);
+        (lower, upper)
+    }
+
     /// Return the Jaccard index between Self and &other
     ///
+    /// The Jaccard index is the size of the intersection divided by the size
+    /// of the union. If both bitmaps are empty, the result is `NaN`.
+    ///
+    /// # Examples
+    ///
     /// ```
     /// use croaring::Bitmap;
     ///
@@ -1200,6 +2346,11 @@ impl Bitmap {
     /// assert_eq!(bitmap1.jaccard_index(&bitmap2), 0.0);
     /// assert_eq!(bitmap1.jaccard_index(&bitmap3), 0.25);
     /// assert_eq!(bitmap2.jaccard_index(&bitmap3), 0.6666666666666666);
+    /// assert_eq!(bitmap1.jaccard_index(&bitmap1), 1.0);
+    ///
+    /// let empty_bitmap = Bitmap::create();
+    /// assert_eq!(bitmap1.jaccard_index(&empty_bitmap), 0.0);
+    /// assert!(empty_bitmap.jaccard_index(&empty_bitmap).is_nan());
     /// ```
     #[inline]
     pub fn jaccard_index(&self, other: &Self) -> f64 {
@@ -1234,6 +2385,7 @@ impl Bitmap {
     /// let bitmap2 = Bitmap::of(&[25]);
     ///
     /// assert_eq!(bitmap1.or_cardinality(&bitmap2), 2);
+    /// ```
     #[inline]
     pub fn or_cardinality(&self, other: &Self) -> u64 {
         unsafe { ffi::roaring_bitmap_or_cardinality(&self.bitmap, &other.bitmap) }
@@ -1273,6 +2425,209 @@ impl Bitmap {
         unsafe { ffi::roaring_bitmap_xor_cardinality(&self.bitmap, &other.bitmap) }
     }
 
+    /// Return the number of values in both Self and &other, the same as
+    /// [`Bitmap::and_cardinality`]
+    ///
+    /// Named to match [`BTreeSet::intersection`](std::collections::BTreeSet::intersection).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use croaring::Bitmap;
+    ///
+    /// let set1: BTreeSet<u32> = [1, 2, 3].into();
+    /// let set2: BTreeSet<u32> = [2, 3, 4].into();
+    /// let bitmap1 = Bitmap::of(&[1, 2, 3]);
+    /// let bitmap2 = Bitmap::of(&[2, 3, 4]);
+    ///
+    /// assert_eq!(bitmap1.intersection_len(&bitmap2), set1.intersection(&set2).count() as u64);
+    /// assert_eq!(bitmap1.intersection_len(&bitmap2), 2);
+    /// ```
+    #[inline]
+    pub fn intersection_len(&self, other: &Self) -> u64 {
+        self.and_cardinality(other)
+    }
+
+    /// Return the number of values in Self or &other, the same as
+    /// [`Bitmap::or_cardinality`]
+    ///
+    /// Named to match [`BTreeSet::union`](std::collections::BTreeSet::union).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use croaring::Bitmap;
+    ///
+    /// let set1: BTreeSet<u32> = [1, 2, 3].into();
+    /// let set2: BTreeSet<u32> = [2, 3, 4].into();
+    /// let bitmap1 = Bitmap::of(&[1, 2, 3]);
+    /// let bitmap2 = Bitmap::of(&[2, 3, 4]);
+    ///
+    /// assert_eq!(bitmap1.union_len(&bitmap2), set1.union(&set2).count() as u64);
+    /// assert_eq!(bitmap1.union_len(&bitmap2), 4);
+    /// ```
+    #[inline]
+    pub fn union_len(&self, other: &Self) -> u64 {
+        self.or_cardinality(other)
+    }
+
+    /// Return the number of values in Self but not in &other, the same as
+    /// [`Bitmap::andnot_cardinality`]
+    ///
+    /// Named to match [`BTreeSet::difference`](std::collections::BTreeSet::difference).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use croaring::Bitmap;
+    ///
+    /// let set1: BTreeSet<u32> = [1, 2, 3].into();
+    /// let set2: BTreeSet<u32> = [2, 3, 4].into();
+    /// let bitmap1 = Bitmap::of(&[1, 2, 3]);
+    /// let bitmap2 = Bitmap::of(&[2, 3, 4]);
+    ///
+    /// assert_eq!(bitmap1.difference_len(&bitmap2), set1.difference(&set2).count() as u64);
+    /// assert_eq!(bitmap1.difference_len(&bitmap2), 1);
+    /// ```
+    #[inline]
+    pub fn difference_len(&self, other: &Self) -> u64 {
+        self.andnot_cardinality(other)
+    }
+
+    /// Return the number of values in exactly one of Self and &other, the same as
+    /// [`Bitmap::xor_cardinality`]
+    ///
+    /// Named to match [`BTreeSet::symmetric_difference`](std::collections::BTreeSet::symmetric_difference).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use croaring::Bitmap;
+    ///
+    /// let set1: BTreeSet<u32> = [1, 2, 3].into();
+    /// let set2: BTreeSet<u32> = [2, 3, 4].into();
+    /// let bitmap1 = Bitmap::of(&[1, 2, 3]);
+    /// let bitmap2 = Bitmap::of(&[2, 3, 4]);
+    ///
+    /// assert_eq!(
+    ///     bitmap1.symmetric_difference_len(&bitmap2),
+    ///     set1.symmetric_difference(&set2).count() as u64
+    /// );
+    /// assert_eq!(bitmap1.symmetric_difference_len(&bitmap2), 2);
+    /// ```
+    #[inline]
+    pub fn symmetric_difference_len(&self, other: &Self) -> u64 {
+        self.xor_cardinality(other)
+    }
+
+    /// Return a new bitmap with the values in Self or &other, the same as
+    /// [`Bitmap::or`]
+    ///
+    /// Named to match [`BTreeSet::union`](std::collections::BTreeSet::union),
+    /// but returns a bitmap rather than an iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use croaring::Bitmap;
+    ///
+    /// let set1: BTreeSet<u32> = [1, 2, 3].into();
+    /// let set2: BTreeSet<u32> = [2, 3, 4].into();
+    /// let bitmap1 = Bitmap::of(&[1, 2, 3]);
+    /// let bitmap2 = Bitmap::of(&[2, 3, 4]);
+    ///
+    /// let union: Vec<u32> = set1.union(&set2).copied().collect();
+    /// assert_eq!(bitmap1.union(&bitmap2), union);
+    /// assert_eq!(bitmap1.union(&bitmap2), [1, 2, 3, 4]);
+    /// ```
+    #[inline]
+    pub fn union(&self, other: &Self) -> Self {
+        self.or(other)
+    }
+
+    /// Return a new bitmap with the values in both Self and &other, the same as
+    /// [`Bitmap::and`]
+    ///
+    /// Named to match [`BTreeSet::intersection`](std::collections::BTreeSet::intersection),
+    /// but returns a bitmap rather than an iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use croaring::Bitmap;
+    ///
+    /// let set1: BTreeSet<u32> = [1, 2, 3].into();
+    /// let set2: BTreeSet<u32> = [2, 3, 4].into();
+    /// let bitmap1 = Bitmap::of(&[1, 2, 3]);
+    /// let bitmap2 = Bitmap::of(&[2, 3, 4]);
+    ///
+    /// let intersection: Vec<u32> = set1.intersection(&set2).copied().collect();
+    /// assert_eq!(bitmap1.intersection(&bitmap2), intersection);
+    /// assert_eq!(bitmap1.intersection(&bitmap2), [2, 3]);
+    /// ```
+    #[inline]
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.and(other)
+    }
+
+    /// Return a new bitmap with the values in Self but not in &other, the same as
+    /// [`Bitmap::andnot`]
+    ///
+    /// Named to match [`BTreeSet::difference`](std::collections::BTreeSet::difference),
+    /// but returns a bitmap rather than an iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use croaring::Bitmap;
+    ///
+    /// let set1: BTreeSet<u32> = [1, 2, 3].into();
+    /// let set2: BTreeSet<u32> = [2, 3, 4].into();
+    /// let bitmap1 = Bitmap::of(&[1, 2, 3]);
+    /// let bitmap2 = Bitmap::of(&[2, 3, 4]);
+    ///
+    /// let difference: Vec<u32> = set1.difference(&set2).copied().collect();
+    /// assert_eq!(bitmap1.difference(&bitmap2), difference);
+    /// assert_eq!(bitmap1.difference(&bitmap2), [1]);
+    /// ```
+    #[inline]
+    pub fn difference(&self, other: &Self) -> Self {
+        self.andnot(other)
+    }
+
+    /// Return a new bitmap with the values in exactly one of Self and &other, the same as
+    /// [`Bitmap::xor`]
+    ///
+    /// Named to match [`BTreeSet::symmetric_difference`](std::collections::BTreeSet::symmetric_difference),
+    /// but returns a bitmap rather than an iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use croaring::Bitmap;
+    ///
+    /// let set1: BTreeSet<u32> = [1, 2, 3].into();
+    /// let set2: BTreeSet<u32> = [2, 3, 4].into();
+    /// let bitmap1 = Bitmap::of(&[1, 2, 3]);
+    /// let bitmap2 = Bitmap::of(&[2, 3, 4]);
+    ///
+    /// let symmetric_difference: Vec<u32> = set1.symmetric_difference(&set2).copied().collect();
+    /// assert_eq!(bitmap1.symmetric_difference(&bitmap2), symmetric_difference);
+    /// assert_eq!(bitmap1.symmetric_difference(&bitmap2), [1, 4]);
+    /// ```
+    #[inline]
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.xor(other)
+    }
+
     /// Returns the smallest value in the set.
     ///
     /// Returns `None` if the set is empty.
@@ -1291,6 +2646,7 @@ impl Bitmap {
     /// bitmap.add(3);
     ///
     /// assert_eq!(bitmap.minimum(), Some(3));
+    /// assert_eq!(bitmap.minimum(), bitmap.iter().next());
     /// ```
     #[inline]
     pub fn minimum(&self) -> Option<u32> {
@@ -1319,6 +2675,7 @@ impl Bitmap {
     /// bitmap.add(15);
     ///
     /// assert_eq!(bitmap.maximum(), Some(15));
+    /// assert_eq!(bitmap.maximum(), bitmap.iter().next_back());
     /// ```
     #[inline]
     pub fn maximum(&self) -> Option<u32> {
@@ -1329,7 +2686,7 @@ impl Bitmap {
         }
     }
 
-    /// Rank returns the number of values smaller or equal to x.
+    /// Rank returns the number of values smaller or equal to `value`.
     ///
     /// # Examples
     ///
@@ -1338,16 +2695,100 @@ impl Bitmap {
     ///
     /// let mut bitmap: Bitmap = (5..10).collect();
     ///
+    /// assert_eq!(bitmap.rank(4), 0);
     /// assert_eq!(bitmap.rank(8), 4);
     ///
     /// bitmap.add(15);
     ///
+    /// // 11 is not in the bitmap
     /// assert_eq!(bitmap.rank(11), 5);
+    /// // 15 is the maximum
     /// assert_eq!(bitmap.rank(15), 6);
+    /// assert_eq!(bitmap.rank(u32::MAX), bitmap.cardinality());
+    ///
+    /// let empty_bitmap = Bitmap::create();
+    /// assert_eq!(empty_bitmap.rank(0), 0);
+    /// assert_eq!(empty_bitmap.rank(u32::MAX), 0);
+    /// ```
+    #[inline]
+    pub fn rank(&self, value: u32) -> u64 {
+        unsafe { ffi::roaring_bitmap_rank(&self.bitmap, value) }
+    }
+
+    /// Returns the number of values in the bitmap which are strictly less than `value`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[0, 5, 10, u32::MAX]);
+    ///
+    /// assert_eq!(bitmap.cardinality_lt(0), 0);
+    /// assert_eq!(bitmap.cardinality_lt(1), 1);
+    /// assert_eq!(bitmap.cardinality_lt(10), 2);
+    /// assert_eq!(bitmap.cardinality_lt(11), 3);
+    /// assert_eq!(bitmap.cardinality_lt(u32::MAX), 3);
+    /// ```
+    #[inline]
+    pub fn cardinality_lt(&self, value: u32) -> u64 {
+        match value.checked_sub(1) {
+            Some(below) => self.rank(below),
+            None => 0,
+        }
+    }
+
+    /// Returns the number of values in the bitmap which are less than or equal to `value`,
+    /// the same as [`Bitmap::rank`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[0, 5, 10, u32::MAX]);
+    ///
+    /// assert_eq!(bitmap.cardinality_le(0), 1);
+    /// assert_eq!(bitmap.cardinality_le(9), 2);
+    /// assert_eq!(bitmap.cardinality_le(10), 3);
+    /// assert_eq!(bitmap.cardinality_le(u32::MAX - 1), 3);
+    /// assert_eq!(bitmap.cardinality_le(u32::MAX), 4);
+    ///
+    /// assert_eq!(Bitmap::create().cardinality_le(u32::MAX), 0);
     /// ```
     #[inline]
-    pub fn rank(&self, x: u32) -> u64 {
-        unsafe { ffi::roaring_bitmap_rank(&self.bitmap, x) }
+    pub fn cardinality_le(&self, value: u32) -> u64 {
+        self.rank(value)
+    }
+
+    /// Returns the 0-based index of `value` among the values in the bitmap,
+    /// or None if `value` is not present.
+    ///
+    /// Unlike [`Bitmap::rank`], values which are not in the bitmap have no index.
+    /// For values which are present, `get_index(value) == Some(rank(value) - 1)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[5, 10, 100_000, u32::MAX]);
+    ///
+    /// assert_eq!(bitmap.get_index(5), Some(0));
+    /// assert_eq!(bitmap.get_index(100_000), Some(2));
+    /// assert_eq!(bitmap.get_index(u32::MAX), Some(3));
+    /// assert_eq!(bitmap.get_index(11), None);
+    /// assert_eq!(bitmap.get_index(0), None);
+    ///
+    /// assert_eq!(Bitmap::create().get_index(0), None);
+    /// ```
+    #[inline]
+    pub fn get_index(&self, value: u32) -> Option<u64> {
+        if self.contains(value) {
+            Some(self.rank(value) - 1)
+        } else {
+            None
+        }
     }
 
     /// Select returns the element having the designated rank, if it exists
@@ -1368,6 +2809,22 @@ impl Bitmap {
     /// assert_eq!(bitmap.select(3), Some(8));
     /// assert_eq!(bitmap.select(4), Some(9));
     /// assert_eq!(bitmap.select(5), None);
+    /// assert_eq!(bitmap.select(u32::MAX), None);
+    ///
+    /// // select is the inverse of rank
+    /// for rank in 0..5 {
+    ///     let value = bitmap.select(rank).unwrap();
+    ///     assert_eq!(bitmap.rank(value) - 1, u64::from(rank));
+    /// }
+    ///
+    /// let sparse_bitmap = Bitmap::of(&[3, 1_000, 70_000, u32::MAX]);
+    ///
+    /// assert_eq!(sparse_bitmap.select(0), Some(3));
+    /// assert_eq!(sparse_bitmap.select(2), Some(70_000));
+    /// assert_eq!(sparse_bitmap.select(3), Some(u32::MAX));
+    /// assert_eq!(sparse_bitmap.select(4), None);
+    ///
+    /// assert_eq!(Bitmap::create().select(0), None);
     /// ```
     #[inline]
     pub fn select(&self, rank: u32) -> Option<u32> {
@@ -1425,11 +2882,51 @@ impl Bitmap {
     /// assert_eq!(statistics.cardinality, 99);
     /// ```
     pub fn statistics(&self) -> Statistics {
-        let mut statistics: ffi::roaring_statistics_s = unsafe { ::std::mem::zeroed() };
+        let mut statistics: ffi::roaring_statistics_s = unsafe { mem::zeroed() };
 
         unsafe { ffi::roaring_bitmap_statistics(&self.bitmap, &mut statistics) };
 
-        statistics
+        Statistics {
+            n_containers: statistics.n_containers,
+            n_array_containers: statistics.n_array_containers,
+            n_run_containers: statistics.n_run_containers,
+            n_bitset_containers: statistics.n_bitset_containers,
+            n_values_array_containers: statistics.n_values_array_containers,
+            n_values_run_containers: statistics.n_values_run_containers,
+            n_values_bitset_containers: statistics.n_values_bitset_containers,
+            n_bytes_array_containers: statistics.n_bytes_array_containers,
+            n_bytes_run_containers: statistics.n_bytes_run_containers,
+            n_bytes_bitset_containers: statistics.n_bytes_bitset_containers,
+            max_value: statistics.max_value,
+            min_value: statistics.min_value,
+            sum_value: statistics.sum_value,
+            cardinality: statistics.cardinality,
+        }
+    }
+
+    /// Returns the number of `(array, run, bitset)` containers in the bitmap
+    ///
+    /// A shortcut for the container counts in [`Bitmap::statistics`], e.g. to check
+    /// whether [`Bitmap::run_optimize`] converted any containers to runs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap: Bitmap = (0..100_000).chain([200_000, 200_002]).collect();
+    /// assert_eq!(bitmap.container_type_counts(), (1, 0, 2));
+    ///
+    /// bitmap.run_optimize();
+    /// assert_eq!(bitmap.container_type_counts(), (1, 2, 0));
+    /// ```
+    pub fn container_type_counts(&self) -> (usize, usize, usize) {
+        let statistics = self.statistics();
+        (
+            statistics.n_array_containers as usize,
+            statistics.n_run_containers as usize,
+            statistics.n_bitset_containers as usize,
+        )
     }
 }
 
@@ -1453,7 +2950,7 @@ fn range_to_inclusive<R: RangeBounds<u32>>(range: R) -> (u32, u32) {
     (start, end)
 }
 
-fn range_to_exclusive<R: RangeBounds<u32>>(range: R) -> (u64, u64) {
+pub(super) fn range_to_exclusive<R: RangeBounds<u32>>(range: R) -> (u64, u64) {
     let start = match range.start_bound() {
         Bound::Included(&i) => u64::from(i),
         Bound::Excluded(&i) => u64::from(i) + 1,
@@ -1,14 +1,32 @@
-use std::iter::{FromIterator, IntoIterator};
-use std::marker::PhantomData;
-use std::pin::Pin;
+use alloc::boxed::Box;
+use alloc::collections::{BTreeSet, BinaryHeap};
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::iter::{FromIterator, FusedIterator, IntoIterator};
+use core::ops::RangeBounds;
+use core::pin::Pin;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+#[cfg(feature = "std")]
+use std::hash::BuildHasher;
 
+use super::imp::range_to_exclusive;
 use super::Bitmap;
 
+/// Iterator over the values in a bitmap, see [`Bitmap::iter`]
+///
+/// Values can be read from both ends, one at a time or in batches. Values are
+/// never returned twice: once the front and back of the iterator meet, all reads
+/// from either end return `None` (or a count of zero).
 #[derive(Clone)]
 pub struct BitmapIterator<'a> {
     iterator: ffi::roaring_uint32_iterator_s,
     rev_iterator: ffi::roaring_uint32_iterator_s,
-    phantom: PhantomData<&'a Bitmap>,
+    remaining: u64,
+    bitmap: &'a Bitmap,
+    // Half-open range of values to iterate over
+    start: u64,
+    end: u64,
 }
 
 unsafe impl Send for BitmapIterator<'_> {}
@@ -16,24 +34,71 @@ unsafe impl Sync for BitmapIterator<'_> {}
 
 impl<'a> BitmapIterator<'a> {
     fn new(bitmap: &'a Bitmap) -> Self {
-        let mut iterator = std::mem::MaybeUninit::uninit();
+        Self::new_range(bitmap, 0, u64::MAX)
+    }
+
+    fn new_range(bitmap: &'a Bitmap, start: u64, end: u64) -> Self {
+        let mut result = BitmapIterator {
+            // Both iterators are fully initialized by `reset`
+            iterator: unsafe { core::mem::zeroed() },
+            rev_iterator: unsafe { core::mem::zeroed() },
+            remaining: 0,
+            bitmap,
+            start,
+            end,
+        };
+        result.reset();
+        result
+    }
+
+    /// Restart the iterator, so it will again yield every value it covers
+    /// from both ends.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, 3]);
+    /// let mut iter = bitmap.iter();
+    ///
+    /// assert_eq!(iter.by_ref().collect::<Vec<_>>(), [1, 2, 3]);
+    /// assert_eq!(iter.next(), None);
+    ///
+    /// iter.reset();
+    /// assert_eq!(iter.next_back(), Some(3));
+    /// assert_eq!(iter.by_ref().collect::<Vec<_>>(), [1, 2]);
+    /// ```
+    pub fn reset(&mut self) {
         unsafe {
-            ffi::roaring_init_iterator(&bitmap.bitmap, iterator.as_mut_ptr());
+            ffi::roaring_init_iterator(&self.bitmap.bitmap, &mut self.iterator);
+            ffi::roaring_init_iterator_last(&self.bitmap.bitmap, &mut self.rev_iterator);
         }
-        let mut rev_iterator = std::mem::MaybeUninit::uninit();
-        unsafe {
-            ffi::roaring_init_iterator_last(&bitmap.bitmap, rev_iterator.as_mut_ptr());
+        if self.start == 0 && self.end > u64::from(u32::MAX) {
+            self.remaining = self.bitmap.cardinality();
+            return;
         }
-        BitmapIterator {
-            iterator: unsafe { iterator.assume_init() },
-            rev_iterator: unsafe { rev_iterator.assume_init() },
-            phantom: PhantomData,
+
+        self.remaining = unsafe {
+            ffi::roaring_bitmap_range_cardinality(&self.bitmap.bitmap, self.start, self.end)
+        };
+        if self.remaining > 0 {
+            // A non-empty range must start and end within the u32 domain
+            let last_in_range = (self.end - 1).min(u64::from(u32::MAX)) as u32;
+            let last = self
+                .bitmap
+                .select((self.bitmap.rank(last_in_range) - 1) as u32)
+                .unwrap();
+            unsafe {
+                ffi::roaring_move_uint32_iterator_equalorlarger(&mut self.iterator, self.start as u32);
+                ffi::roaring_move_uint32_iterator_equalorlarger(&mut self.rev_iterator, last);
+            }
         }
     }
 
     #[inline]
     fn current_value(&self) -> Option<u32> {
-        if self.has_value() {
+        if self.remaining > 0 && self.has_value() {
             Some(self.iterator.current_value)
         } else {
             None
@@ -52,7 +117,7 @@ impl<'a> BitmapIterator<'a> {
 
     #[inline]
     fn current_value_back(&self) -> Option<u32> {
-        if self.has_value_back() {
+        if self.remaining > 0 && self.has_value_back() {
             Some(self.rev_iterator.current_value)
         } else {
             None
@@ -69,6 +134,111 @@ impl<'a> BitmapIterator<'a> {
         unsafe { ffi::roaring_previous_uint32_iterator(&mut self.rev_iterator) }
     }
 
+    /// Move the iterator forward to the first value which is `>= target`
+    ///
+    /// Returns the new current value, which will be returned by the next call to `next`,
+    /// or `None` if there are no remaining values `>= target`. If the current value is
+    /// already `>= target`, the iterator is not moved.
+    ///
+    /// This is much more efficient than skipping values one at a time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 5, 10, 100_000, 200_000]);
+    /// let mut iter = bitmap.iter();
+    ///
+    /// assert_eq!(iter.advance_to(3), Some(5));
+    /// assert_eq!(iter.next(), Some(5));
+    ///
+    /// // Seek to an exact value
+    /// assert_eq!(iter.advance_to(100_000), Some(100_000));
+    /// assert_eq!(iter.len(), 2);
+    ///
+    /// // Seeking backwards does nothing
+    /// assert_eq!(iter.advance_to(0), Some(100_000));
+    ///
+    /// // Seek beyond the maximum
+    /// assert_eq!(iter.advance_to(300_000), None);
+    /// assert_eq!(iter.next(), None);
+    /// assert_eq!(iter.len(), 0);
+    /// ```
+    pub fn advance_to(&mut self, target: u32) -> Option<u32> {
+        let current = self.current_value()?;
+        if current >= target {
+            return Some(current);
+        }
+        // Values already returned from the back are not available
+        let last = self.current_value_back()?;
+        if last < target {
+            self.remaining = 0;
+            return None;
+        }
+
+        let skipped = self.bitmap.range_cardinality(current..target);
+        unsafe {
+            ffi::roaring_move_uint32_iterator_equalorlarger(&mut self.iterator, target);
+        }
+        self.remaining -= skipped;
+        self.current_value()
+    }
+
+    /// Returns the value which will be returned by the next call to `next`,
+    /// without advancing the iterator
+    ///
+    /// Unlike [`std::iter::Peekable`], this leaves the iterator itself available,
+    /// so it can be combined with [`BitmapIterator::next_many`] and
+    /// [`BitmapIterator::advance_to`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, 3]);
+    /// let mut iter = bitmap.iter();
+    ///
+    /// assert_eq!(iter.peek(), Some(1));
+    /// assert_eq!(iter.peek(), Some(1));
+    /// assert_eq!(iter.next(), Some(1));
+    ///
+    /// let mut buf = [0; 2];
+    /// assert_eq!(iter.next_many(&mut buf), 2);
+    /// assert_eq!(iter.peek(), None);
+    /// ```
+    #[inline]
+    pub fn peek(&self) -> Option<u32> {
+        self.current_value()
+    }
+
+    /// Returns the value which will be returned by the next call to `next_back`,
+    /// without advancing the iterator
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, 3]);
+    /// let mut iter = bitmap.iter();
+    ///
+    /// assert_eq!(iter.peek_back(), Some(3));
+    /// assert_eq!(iter.next_back(), Some(3));
+    /// assert_eq!(iter.next_back(), Some(2));
+    ///
+    /// // The front and back of the iterator meet at the last value
+    /// assert_eq!(iter.peek(), Some(1));
+    /// assert_eq!(iter.peek_back(), Some(1));
+    /// assert_eq!(iter.next(), Some(1));
+    /// assert_eq!(iter.peek_back(), None);
+    /// ```
+    #[inline]
+    pub fn peek_back(&self) -> Option<u32> {
+        self.current_value_back()
+    }
+
     /// Attempt to read many values from the iterator into `dst`
     ///
     /// Returns the number of items read from the iterator, may be `< dst.len()` iff
@@ -121,13 +291,118 @@ impl<'a> BitmapIterator<'a> {
     /// ```
     #[inline]
     pub fn next_many(&mut self, dst: &mut [u32]) -> usize {
-        let count: u32 = u32::try_from(dst.len()).unwrap_or(u32::MAX);
+        let count: u32 = read_count(dst.len(), self.remaining);
         let result = unsafe {
             ffi::roaring_read_uint32_iterator(&mut self.iterator, dst.as_mut_ptr(), count)
         };
         debug_assert!(result <= count);
+        self.remaining -= u64::from(result);
         result as usize
     }
+
+    /// Attempt to read many values from the back of the iterator into `dst`,
+    /// in descending order
+    ///
+    /// Returns the number of items read from the iterator, may be `< dst.len()` iff
+    /// the iterator is exhausted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap: Bitmap = Bitmap::create();
+    /// bitmap.add_range(0..100);
+    /// bitmap.add(222);
+    /// bitmap.add(555);
+    ///
+    /// let mut buf = [0; 100];
+    /// let mut iter = bitmap.iter();
+    /// assert_eq!(iter.next_many_back(&mut buf), 100);
+    /// // Get the last 100 items, including the two added after the range
+    /// assert_eq!(buf[0], 555);
+    /// assert_eq!(buf[1], 222);
+    /// for (i, item) in buf[2..].iter().enumerate() {
+    ///     assert_eq!(*item, 99 - i as u32);
+    /// }
+    /// // Calls to next_many_back() can be interleaved with calls to next_back() and next_many()
+    /// assert_eq!(iter.next_back(), Some(1));
+    /// assert_eq!(iter.next_many(&mut buf[..1]), 1);
+    /// assert_eq!(buf[0], 0);
+    ///
+    /// assert_eq!(iter.next_back(), None);
+    /// assert_eq!(iter.next_many_back(&mut buf), 0);
+    /// ```
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// fn print_by_chunks_descending(bitmap: &Bitmap) {
+    ///     let mut buf = [0; 1024];
+    ///     let mut iter = bitmap.iter();
+    ///     loop {
+    ///         let n = iter.next_many_back(&mut buf);
+    ///         if n == 0 {
+    ///             break;
+    ///         }
+    ///         println!("{:?}", &buf[..n]);
+    ///     }
+    /// }
+    ///
+    /// # print_by_chunks_descending(&Bitmap::of(&[1, 2, 8, 20, 1000]));
+    /// ```
+    pub fn next_many_back(&mut self, dst: &mut [u32]) -> usize {
+        let mut count = 0;
+        for item in dst.iter_mut() {
+            match self.next_back() {
+                Some(value) => *item = value,
+                None => break,
+            }
+            count += 1;
+        }
+        count
+    }
+
+    /// Appends all remaining values to `dst` in ascending order, leaving the
+    /// iterator exhausted
+    ///
+    /// Values are read in bulk with [`BitmapIterator::next_many`], which is much
+    /// faster than `dst.extend(iter)` or `iter.collect::<Vec<u32>>()`, as those read
+    /// one value at a time. To export a whole bitmap, [`Bitmap::to_vec`] is faster still.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap: Bitmap = (0..10).chain(100_000..100_005).collect();
+    ///
+    /// let mut iter = bitmap.iter();
+    /// iter.advance_to(8);
+    /// iter.next_back();
+    ///
+    /// let mut values = vec![1, 2];
+    /// iter.collect_into(&mut values);
+    /// assert_eq!(values, [1, 2, 8, 9, 100_000, 100_001, 100_002, 100_003]);
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn collect_into(&mut self, dst: &mut Vec<u32>) {
+        // If the count doesn't fit in a usize, the values can't fit in a Vec either,
+        // and the resize fails as any other Vec allocation which is too large
+        let additional = usize::try_from(self.remaining).unwrap_or(usize::MAX);
+        let start = dst.len();
+        dst.resize(start.saturating_add(additional), 0);
+
+        let mut end = start;
+        while end < dst.len() {
+            let n = self.next_many(&mut dst[end..]);
+            if n == 0 {
+                break;
+            }
+            end += n;
+        }
+        dst.truncate(end);
+    }
 }
 
 impl<'a> Iterator for BitmapIterator<'a> {
@@ -137,12 +412,81 @@ impl<'a> Iterator for BitmapIterator<'a> {
         match self.current_value() {
             Some(value) => {
                 self.advance();
+                self.remaining -= 1;
 
                 Some(value)
             }
             None => None,
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        remaining_size_hint(self.remaining)
+    }
+
+    /// Returns the number of remaining values without iterating over them
+    ///
+    /// # Panics
+    ///
+    /// Like any iterator with more than `usize::MAX` items, panics if the count
+    /// doesn't fit in a `usize`, which is only possible on 32 bit targets.
+    #[inline]
+    fn count(self) -> usize {
+        remaining_count(self.remaining)
+    }
+
+    /// Returns the last remaining value without iterating over the values before it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, 3, 100_000]);
+    /// assert_eq!(bitmap.iter().last(), bitmap.maximum());
+    ///
+    /// let mut iter = bitmap.iter();
+    /// iter.next_back();
+    /// assert_eq!(iter.last(), Some(3));
+    /// ```
+    #[inline]
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    /// Returns the `n`th remaining value, seeking to it rather than stepping
+    /// over each value before it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::from_range(0..1_000_000);
+    /// let mut iter = bitmap.iter();
+    ///
+    /// assert_eq!(iter.nth(10), Some(10));
+    /// assert_eq!(iter.nth(500_000), Some(500_011));
+    /// assert_eq!(iter.next(), Some(500_012));
+    ///
+    /// iter.next_back();
+    /// assert_eq!(iter.nth(499_985), Some(999_998));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let current = self.current_value()?;
+        let n = n as u64;
+        if n >= self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+
+        let index = self.bitmap.rank(current) - 1 + n;
+        let target = self.bitmap.select(index as u32).unwrap();
+        self.advance_to(target);
+        self.next()
+    }
 }
 
 impl<'a> DoubleEndedIterator for BitmapIterator<'a> {
@@ -150,6 +494,7 @@ impl<'a> DoubleEndedIterator for BitmapIterator<'a> {
         match self.current_value_back() {
             Some(value) => {
                 self.advance_back();
+                self.remaining -= 1;
 
                 Some(value)
             }
@@ -158,6 +503,37 @@ impl<'a> DoubleEndedIterator for BitmapIterator<'a> {
     }
 }
 
+/// The number of remaining items is tracked, so forward and backward
+/// iteration never yield the same value twice.
+///
+/// A full bitmap has 2^32 values, which doesn't fit in a `usize` on 32 bit targets.
+/// There, while more than `usize::MAX` values remain, `size_hint` returns
+/// `(usize::MAX, None)` and `len` panics.
+///
+/// # Examples
+///
+/// ```
+/// use croaring::Bitmap;
+///
+/// let bitmap = Bitmap::of(&[1, 2, 3, 4, 5]);
+/// let mut iter = bitmap.iter();
+/// assert_eq!(iter.len(), 5);
+///
+/// iter.next();
+/// iter.next_back();
+/// assert_eq!(iter.len(), 3);
+///
+/// let mut buf = [0; 10];
+/// assert_eq!(iter.next_many(&mut buf), 3);
+/// assert_eq!(&buf[..3], [2, 3, 4]);
+/// assert_eq!(iter.len(), 0);
+/// assert_eq!(iter.next_back(), None);
+/// ```
+impl<'a> ExactSizeIterator for BitmapIterator<'a> {}
+
+/// Once exhausted, the iterator keeps returning `None` until it is [`reset`](BitmapIterator::reset).
+impl<'a> FusedIterator for BitmapIterator<'a> {}
+
 impl Bitmap {
     /// Returns an iterator over each value stored in the bitmap.
     /// Returned values are ordered in ascending order.
@@ -181,8 +557,321 @@ impl Bitmap {
     pub fn iter(&self) -> BitmapIterator {
         BitmapIterator::new(self)
     }
+
+    /// Returns an iterator over each value stored in the bitmap which is within `range`.
+    /// Returned values are ordered in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 5, 10, 100_000, 200_000, u32::MAX]);
+    ///
+    /// let values: Vec<u32> = bitmap.iter_range(5..200_000).collect();
+    /// assert_eq!(values, [5, 10, 100_000]);
+    ///
+    /// let values: Vec<u32> = bitmap.iter_range(6..=200_000).rev().collect();
+    /// assert_eq!(values, [200_000, 100_000, 10]);
+    ///
+    /// let mut iter = bitmap.iter_range(100_000..);
+    /// let mut buf = [0; 10];
+    /// assert_eq!(iter.next_many(&mut buf), 3);
+    /// assert_eq!(&buf[..3], [100_000, 200_000, u32::MAX]);
+    ///
+    /// assert_eq!(bitmap.iter_range(11..100).next(), None);
+    /// assert_eq!(bitmap.iter_range(100..1).next(), None);
+    /// ```
+    pub fn iter_range<R: RangeBounds<u32>>(&self, range: R) -> BitmapIterator {
+        let (start, end) = range_to_exclusive(range);
+        BitmapIterator::new_range(self, start, end)
+    }
+
+    /// Returns an iterator over each maximal run of consecutive values stored in the bitmap,
+    /// as `(start, length)` pairs. Returned runs are ordered in ascending order.
+    ///
+    /// A run cannot be longer than `u32::MAX`, so a bitmap containing every `u32` will
+    /// return two runs: `(0, u32::MAX)` and `(u32::MAX, 1)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, 3, 7, 8]);
+    /// let runs: Vec<(u32, u32)> = bitmap.iter_runs().collect();
+    ///
+    /// assert_eq!(runs, [(1, 3), (7, 2)]);
+    ///
+    /// let mut bitmap = Bitmap::from_range(100..200_000);
+    /// bitmap.add(u32::MAX);
+    /// let runs: Vec<(u32, u32)> = bitmap.iter_runs().collect();
+    ///
+    /// assert_eq!(runs, [(100, 199_900), (u32::MAX, 1)]);
+    /// ```
+    pub fn iter_runs(&self) -> RunIterator {
+        RunIterator {
+            iter: self.iter(),
+            bitmap: self,
+        }
+    }
+
+    /// Returns an iterator which splits the bitmap into one bitmap per container,
+    /// as `(high_bits, bitmap)` pairs. Each bitmap contains exactly the values whose
+    /// high 16 bits are `high_bits`, and pairs are ordered by `high_bits`.
+    ///
+    /// The split is lossless, so the pieces can be processed independently (e.g. on
+    /// separate threads) and recombined with [`Bitmap::fast_or`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, 70_000, u32::MAX]);
+    /// let containers: Vec<(u16, Bitmap)> = bitmap.containers().collect();
+    ///
+    /// assert_eq!(containers.len(), 3);
+    /// assert_eq!(containers[0], (0, Bitmap::of(&[1, 2])));
+    /// assert_eq!(containers[1], (1, Bitmap::of(&[70_000])));
+    /// assert_eq!(containers[2], (u16::MAX, Bitmap::of(&[u32::MAX])));
+    ///
+    /// let pieces: Vec<&Bitmap> = containers.iter().map(|(_, piece)| piece).collect();
+    /// assert_eq!(Bitmap::fast_or(&pieces), bitmap);
+    ///
+    /// assert_eq!(Bitmap::create().containers().next(), None);
+    /// ```
+    pub fn containers(&self) -> ContainerIterator {
+        ContainerIterator {
+            iter: self.iter(),
+            bitmap: self,
+            next_start: Some(0),
+        }
+    }
+
+    /// Returns an iterator over the union of several bitmaps, without
+    /// materializing it
+    ///
+    /// Values are returned in ascending order, and values present in more than
+    /// one bitmap are returned once. The iterator yields the same values as
+    /// iterating over [`Bitmap::fast_or`], which is usually faster if every
+    /// value is needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap1 = Bitmap::of(&[1, 5, 70_000]);
+    /// let bitmap2 = Bitmap::of(&[2, 5, u32::MAX]);
+    /// let bitmap3 = Bitmap::of(&[5]);
+    ///
+    /// let merged: Vec<u32> = Bitmap::merge_iter(&[&bitmap1, &bitmap2, &bitmap3]).collect();
+    /// assert_eq!(merged, [1, 2, 5, 70_000, u32::MAX]);
+    ///
+    /// assert_eq!(Bitmap::merge_iter(&[]).next(), None);
+    /// ```
+    pub fn merge_iter<'a>(bitmaps: &[&'a Bitmap]) -> MergeIterator<'a> {
+        let iters: Vec<BitmapIterator<'a>> = bitmaps.iter().map(|bitmap| bitmap.iter()).collect();
+        let heap = iters
+            .iter()
+            .enumerate()
+            .filter_map(|(i, iter)| iter.peek().map(|value| Reverse((value, i))))
+            .collect();
+        MergeIterator { iters, heap }
+    }
+
+    /// Returns an iterator over the intersection of several bitmaps, without
+    /// materializing it
+    ///
+    /// Values are returned in ascending order. Each bitmap's iterator skips ahead
+    /// with [`BitmapIterator::advance_to`], so values missing from any bitmap are
+    /// skipped efficiently. The iterator yields the same values as iterating over
+    /// the result of [`Bitmap::and`].
+    ///
+    /// The intersection of an empty slice yields no values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap1 = Bitmap::of(&[1, 5, 70_000, u32::MAX]);
+    /// let bitmap2 = Bitmap::of(&[2, 5, 70_000, u32::MAX]);
+    /// let bitmap3 = Bitmap::from_range(5..=70_000);
+    ///
+    /// let common: Vec<u32> = Bitmap::intersect_iter(&[&bitmap1, &bitmap2, &bitmap3]).collect();
+    /// assert_eq!(common, [5, 70_000]);
+    ///
+    /// assert_eq!(Bitmap::intersect_iter(&[]).next(), None);
+    /// ```
+    pub fn intersect_iter<'a>(bitmaps: &[&'a Bitmap]) -> IntersectIterator<'a> {
+        IntersectIterator {
+            iters: bitmaps.iter().map(|bitmap| bitmap.iter()).collect(),
+        }
+    }
+}
+
+/// Iterator over the containers in a bitmap, see [`Bitmap::containers`]
+#[derive(Clone)]
+pub struct ContainerIterator<'a> {
+    iter: BitmapIterator<'a>,
+    bitmap: &'a Bitmap,
+    // The first value of the next container to look for, None once the
+    // last possible container has been returned
+    next_start: Option<u32>,
+}
+
+impl<'a> Iterator for ContainerIterator<'a> {
+    type Item = (u16, Bitmap);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.iter.advance_to(self.next_start?)?;
+        let high_bits = (value >> 16) as u16;
+        let start = u32::from(high_bits) << 16;
+        let end = start | 0xFFFF;
+
+        self.next_start = end.checked_add(1);
+        Some((high_bits, self.bitmap.and(&Bitmap::from_range(start..=end))))
+    }
+}
+
+impl<'a> FusedIterator for ContainerIterator<'a> {}
+
+/// Iterator over the union of several bitmaps, see [`Bitmap::merge_iter`]
+#[derive(Clone)]
+pub struct MergeIterator<'a> {
+    iters: Vec<BitmapIterator<'a>>,
+    // The current value of each non-exhausted iterator, with its index in `iters`
+    heap: BinaryHeap<Reverse<(u32, usize)>>,
+}
+
+impl<'a> MergeIterator<'a> {
+    #[inline]
+    fn advance(&mut self, i: usize) {
+        let iter = &mut self.iters[i];
+        iter.next();
+        if let Some(value) = iter.peek() {
+            self.heap.push(Reverse((value, i)));
+        }
+    }
+}
+
+impl<'a> Iterator for MergeIterator<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((value, i)) = self.heap.pop()?;
+        self.advance(i);
+        // Skip the same value in every other bitmap
+        while let Some(&Reverse((next_value, next_i))) = self.heap.peek() {
+            if next_value != value {
+                break;
+            }
+            self.heap.pop();
+            self.advance(next_i);
+        }
+        Some(value)
+    }
+}
+
+impl<'a> FusedIterator for MergeIterator<'a> {}
+
+/// Iterator over the intersection of several bitmaps, see [`Bitmap::intersect_iter`]
+#[derive(Clone)]
+pub struct IntersectIterator<'a> {
+    iters: Vec<BitmapIterator<'a>>,
+}
+
+impl<'a> Iterator for IntersectIterator<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut candidate = self.iters.first()?.peek()?;
+        // Visit the iterators round robin, until every one agrees on the candidate
+        let mut agreed = 0;
+        let mut i = 0;
+        while agreed < self.iters.len() {
+            let value = self.iters[i].advance_to(candidate)?;
+            if value == candidate {
+                agreed += 1;
+            } else {
+                candidate = value;
+                agreed = 1;
+            }
+            i = (i + 1) % self.iters.len();
+        }
+
+        for iter in &mut self.iters {
+            iter.next();
+        }
+        Some(candidate)
+    }
+}
+
+impl<'a> FusedIterator for IntersectIterator<'a> {}
+
+/// Iterator over runs of consecutive values in a bitmap, see [`Bitmap::iter_runs`]
+#[derive(Clone)]
+pub struct RunIterator<'a> {
+    iter: BitmapIterator<'a>,
+    bitmap: &'a Bitmap,
+}
+
+impl<'a> RunIterator<'a> {
+    /// Returns true if all values in `[start, start + length)` are present
+    #[inline]
+    fn contains_run(&self, start: u32, length: u64) -> bool {
+        let last = u64::from(start) + length - 1;
+        self.bitmap.contains_range(start..=last as u32)
+    }
 }
 
+impl<'a> Iterator for RunIterator<'a> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.iter.next()?;
+        let max_length = ((1 << 32) - u64::from(start)).min(u64::from(u32::MAX));
+
+        // Gallop to find a length which is not contained, then binary search
+        // between the longest known contained length and it
+        let mut low: u64 = 1;
+        let mut step: u64 = 1;
+        let mut high = loop {
+            let probe = (low + step).min(max_length);
+            if probe == low {
+                break max_length + 1;
+            }
+            if self.contains_run(start, probe) {
+                low = probe;
+                step *= 2;
+            } else {
+                break probe;
+            }
+        };
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if self.contains_run(start, mid) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        if let Ok(end) = u32::try_from(u64::from(start) + low) {
+            self.iter.advance_to(end);
+        } else {
+            // The run ends at u32::MAX, so that is the only value left to skip
+            self.iter.advance_to(u32::MAX);
+            self.iter.next();
+        }
+        Some((start, low as u32))
+    }
+}
+
+impl<'a> FusedIterator for RunIterator<'a> {}
+
 impl FromIterator<u32> for Bitmap {
     /// Convenience method for creating bitmap from iterator.
     ///
@@ -204,17 +893,110 @@ impl FromIterator<u32> for Bitmap {
 }
 
 impl Extend<u32> for Bitmap {
+    /// Adds the values from an iterator, buffering them into chunks which are
+    /// added with [`Bitmap::add_many`]
+    ///
+    /// If the values are known to be sorted, [`Bitmap::extend_sorted`] is faster.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::of(&[1]);
+    /// bitmap.extend([100_000, 3, 2, 3]);
+    ///
+    /// assert_eq!(bitmap.to_vec(), [1, 2, 3, 100_000]);
+    /// ```
     fn extend<T: IntoIterator<Item=u32>>(&mut self, iter: T) {
-        for item in iter {
-            self.add(item);
+        const CHUNK_SIZE: usize = 1024;
+
+        let mut iter = iter.into_iter();
+        let mut buffer = [0; CHUNK_SIZE];
+        loop {
+            let mut len = 0;
+            for (slot, value) in buffer.iter_mut().zip(&mut iter) {
+                *slot = value;
+                len += 1;
+            }
+            self.add_many(&buffer[..len]);
+            if len < CHUNK_SIZE {
+                break;
+            }
         }
     }
 }
 
+impl From<&BTreeSet<u32>> for Bitmap {
+    /// Creates a bitmap containing the values in a `BTreeSet`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use croaring::Bitmap;
+    ///
+    /// let set: BTreeSet<u32> = [1, 2, 100_000].into();
+    /// let bitmap = Bitmap::from(&set);
+    ///
+    /// assert_eq!(bitmap.to_vec(), [1, 2, 100_000]);
+    /// ```
+    fn from(set: &BTreeSet<u32>) -> Self {
+        let values: Vec<u32> = set.iter().copied().collect();
+        Bitmap::from_sorted_slice(&values).unwrap()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: BuildHasher> From<&HashSet<u32, S>> for Bitmap {
+    /// Creates a bitmap containing the values in a `HashSet`
+    ///
+    /// Requires the `std` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    /// use croaring::Bitmap;
+    ///
+    /// let set: HashSet<u32> = [100_000, 2, 1].into();
+    /// let bitmap = Bitmap::from(&set);
+    ///
+    /// assert_eq!(bitmap.to_vec(), [1, 2, 100_000]);
+    /// ```
+    fn from(set: &HashSet<u32, S>) -> Self {
+        let values: Vec<u32> = set.iter().copied().collect();
+        Bitmap::of(&values)
+    }
+}
+
+impl From<&Bitmap> for BTreeSet<u32> {
+    /// Creates a `BTreeSet` containing the values in a bitmap
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, 100_000]);
+    /// let set = BTreeSet::from(&bitmap);
+    ///
+    /// assert_eq!(set, [1, 2, 100_000].into());
+    /// ```
+    fn from(bitmap: &Bitmap) -> Self {
+        bitmap.iter().collect()
+    }
+}
+
+/// Owning iterator over the values in a bitmap, see [`Bitmap::into_iter`]
+///
+/// Like [`BitmapIterator`], values are never returned twice when reading from both ends.
 #[derive(Clone)]
 pub struct BitmapIntoIterator {
     iterator: ffi::roaring_uint32_iterator_s,
     rev_iterator: ffi::roaring_uint32_iterator_s,
+    remaining: u64,
     _bitmap: Pin<Box<Bitmap>>,
 }
 
@@ -224,17 +1006,18 @@ unsafe impl Sync for BitmapIntoIterator {}
 impl<'a> BitmapIntoIterator {
     fn new(bitmap: Bitmap) -> Self {
         let bitmap = Box::pin(bitmap);
-        let mut iterator = std::mem::MaybeUninit::uninit();
+        let mut iterator = core::mem::MaybeUninit::uninit();
         unsafe {
             ffi::roaring_init_iterator(&bitmap.bitmap, iterator.as_mut_ptr());
         }
-        let mut rev_iterator = std::mem::MaybeUninit::uninit();
+        let mut rev_iterator = core::mem::MaybeUninit::uninit();
         unsafe {
             ffi::roaring_init_iterator_last(&bitmap.bitmap, rev_iterator.as_mut_ptr());
         }
         BitmapIntoIterator {
             iterator: unsafe { iterator.assume_init() },
             rev_iterator: unsafe { rev_iterator.assume_init() },
+            remaining: bitmap.cardinality(),
             _bitmap: bitmap,
         }
     }
@@ -291,11 +1074,12 @@ impl<'a> BitmapIntoIterator {
     /// ```
     #[inline]
     pub fn next_many(&mut self, dst: &mut [u32]) -> usize {
-        let count: u32 = u32::try_from(dst.len()).unwrap_or(u32::MAX);
+        let count: u32 = read_count(dst.len(), self.remaining);
         let result = unsafe {
             ffi::roaring_read_uint32_iterator(&mut self.iterator, dst.as_mut_ptr(), count)
         };
         debug_assert!(result <= count);
+        self.remaining -= u64::from(result);
         result as usize
     }
 }
@@ -304,22 +1088,40 @@ impl Iterator for BitmapIntoIterator {
     type Item = u32;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let ret = if self.iterator.has_value {
+        let ret = if self.remaining > 0 && self.iterator.has_value {
             let value = self.iterator.current_value;
             unsafe { ffi::roaring_advance_uint32_iterator(&mut self.iterator) };
+            self.remaining -= 1;
             Some(value)
         }else{
             None
         };
         ret
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        remaining_size_hint(self.remaining)
+    }
+
+    /// Returns the number of remaining values without iterating over them
+    ///
+    /// # Panics
+    ///
+    /// Like any iterator with more than `usize::MAX` items, panics if the count
+    /// doesn't fit in a `usize`, which is only possible on 32 bit targets.
+    #[inline]
+    fn count(self) -> usize {
+        remaining_count(self.remaining)
+    }
 }
 
 impl DoubleEndedIterator for BitmapIntoIterator {
     fn next_back(&mut self) -> Option<Self::Item> {
-        let ret = if self.rev_iterator.has_value {
+        let ret = if self.remaining > 0 && self.rev_iterator.has_value {
             let value = self.rev_iterator.current_value;
             unsafe { ffi::roaring_previous_uint32_iterator(&mut self.rev_iterator) };
+            self.remaining -= 1;
             Some(value)
         }else{
             None
@@ -328,6 +1130,12 @@ impl DoubleEndedIterator for BitmapIntoIterator {
     }
 }
 
+/// The same as for [`BitmapIterator`], `len` panics on 32 bit targets while more
+/// than `usize::MAX` values remain.
+impl ExactSizeIterator for BitmapIntoIterator {}
+
+impl FusedIterator for BitmapIntoIterator {}
+
 impl IntoIterator for Bitmap {
     type Item = u32;
     type IntoIter = BitmapIntoIterator;
@@ -335,4 +1143,27 @@ impl IntoIterator for Bitmap {
     fn into_iter(self) -> Self::IntoIter {
         BitmapIntoIterator::new(self)
     }
-}
\ No newline at end of file
+}
+
+/// The number of items to request from CRoaring when reading into a buffer of `len` items,
+/// without reading past the `remaining` items
+#[inline]
+fn read_count(len: usize, remaining: u64) -> u32 {
+    let count = u64::try_from(len).unwrap_or(u64::MAX).min(remaining);
+    u32::try_from(count).unwrap_or(u32::MAX)
+}
+
+#[inline]
+fn remaining_count(remaining: u64) -> usize {
+    usize::try_from(remaining).expect("more than usize::MAX values remain")
+}
+
+/// Exact while the count fits in a usize. Otherwise there are more than
+/// `usize::MAX` values, and no upper bound can be given
+#[inline]
+fn remaining_size_hint(remaining: u64) -> (usize, Option<usize>) {
+    match usize::try_from(remaining) {
+        Ok(n) => (n, Some(n)),
+        Err(_) => (usize::MAX, None),
+    }
+}
@@ -1,5 +1,5 @@
-use std::fmt;
-use std::ops::{BitAnd, SubAssign};
+use core::fmt;
+use core::ops::{BitAnd, SubAssign};
 use crate::Bitmap;
 
 pub struct LazyBitmap<'a> {
@@ -36,22 +36,61 @@ impl<'a> LazyBitmap<'a> {
         }
         self
     }
+
+    /// Modifies the bitmap this lazy bitmap is associated with to be the intersection of the two bitmaps.
+    ///
+    /// Intersections can be freely mixed with the other lazy operations, in any order: the
+    /// result accounts for containers left unrepaired by earlier lazy unions and xors.
+    /// Unlike those operations, the intersection is computed into a new bitmap which then
+    /// replaces the associated bitmap, rather than modifying its containers in place.
+    #[inline]
+    pub fn and_inplace(&mut self, other: &Bitmap) -> &mut Self {
+        *self.bitmap = unsafe {
+            Bitmap::take_heap(ffi::roaring_bitmap_lazy_and(&self.bitmap.bitmap, &other.bitmap))
+        };
+        self
+    }
+
+    /// Modifies the bitmap this lazy bitmap is associated with to be the difference of the two bitmaps.
+    #[inline]
+    pub fn andnot_inplace(&mut self, other: &Bitmap) -> &mut Self {
+        unsafe {
+            // Because we have a mutable borrow of the bitmap, `other` cannot be == our bitmap,
+            // so this is always safe
+            ffi::roaring_bitmap_lazy_andnot_inplace(&mut self.bitmap.bitmap, &other.bitmap);
+        }
+        self
+    }
 }
 
-impl<'a> std::ops::BitOrAssign<&Bitmap> for LazyBitmap<'a> {
+impl<'a> core::ops::BitOrAssign<&Bitmap> for LazyBitmap<'a> {
     #[inline]
     fn bitor_assign(&mut self, other: &Bitmap) {
         self.or_inplace(other, false);
     }
 }
 
-impl<'a> std::ops::BitXorAssign<&Bitmap> for LazyBitmap<'a> {
+impl<'a> core::ops::BitXorAssign<&Bitmap> for LazyBitmap<'a> {
     #[inline]
     fn bitxor_assign(&mut self, other: &Bitmap) {
         self.xor_inplace(other);
     }
 }
 
+impl<'a> core::ops::BitAndAssign<&Bitmap> for LazyBitmap<'a> {
+    #[inline]
+    fn bitand_assign(&mut self, other: &Bitmap) {
+        self.and_inplace(other);
+    }
+}
+
+impl<'a> SubAssign<&Bitmap> for LazyBitmap<'a> {
+    #[inline]
+    fn sub_assign(&mut self, other: &Bitmap) {
+        self.andnot_inplace(other);
+    }
+}
+
 impl Bitmap {
     /// Perform multiple bitwise operations on a bitmap.
     ///
@@ -148,13 +187,15 @@ impl Bitmap {
         unsafe {
             ffi::roaring_bitmap_convert_to_lazy(&mut self.bitmap);
         }
-        LazyOwnedBitmap { bitmap: self }
+        LazyOwnedBitmap { bitmap: self, repaired: false }
     }
 }
 
 #[derive(Clone)]
 pub struct LazyOwnedBitmap {
     bitmap: Bitmap,
+    // True if no lazy operations have been performed since the bitmap was last repaired
+    repaired: bool,
 }
 
 impl LazyOwnedBitmap {
@@ -162,7 +203,8 @@ impl LazyOwnedBitmap {
     #[inline]
     pub fn create() -> Self {
         LazyOwnedBitmap {
-            bitmap: Bitmap::create()
+            bitmap: Bitmap::create(),
+            repaired: true,
         }
     }
 
@@ -173,6 +215,7 @@ impl LazyOwnedBitmap {
     /// * `force_bitsets` - Whether to force conversions to bitsets when modifying containers
     #[inline]
     pub fn or_inplace(&mut self, other: &Bitmap, force_bitsets: bool) -> &mut Self {
+        self.repaired = false;
         unsafe {
             // Because we have a mutable borrow of the bitmap, `other` cannot be == our bitmap,
             // so this is always safe
@@ -187,6 +230,7 @@ impl LazyOwnedBitmap {
 
     #[inline]
     pub fn or_inplace_owned(&mut self, other: &mut Bitmap, force_bitsets: bool) -> &mut Self {
+        self.repaired = false;
         unsafe {
             // Because we have a mutable borrow of the bitmap, `other` cannot be == our bitmap,
             // so this is always safe
@@ -201,15 +245,72 @@ impl LazyOwnedBitmap {
 
     #[inline]
     pub fn add(&mut self, element: u32) {
+        self.repaired = false;
         unsafe { ffi::roaring_bitmap_lazy_add(&mut self.bitmap.bitmap, element) }
     }
 
-    pub fn into_inner(self) -> Bitmap {
-        let mut bitmap = self.bitmap;
-        unsafe {
-            ffi::roaring_bitmap_repair_after_lazy(&mut bitmap.bitmap);
+    pub fn into_inner(mut self) -> Bitmap {
+        self.repair();
+        self.bitmap
+    }
+
+    /// Repairs the bitmap in place, finalizing the results of all lazy operations so far.
+    ///
+    /// Further lazy operations can still be performed afterwards. Until then, queries
+    /// such as [`LazyOwnedBitmap::cardinality`] are answered directly from the bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::bitmap::LazyOwnedBitmap;
+    /// use croaring::Bitmap;
+    ///
+    /// let mut lazy = LazyOwnedBitmap::create();
+    /// lazy |= &Bitmap::from_range(0..10_000);
+    /// lazy |= &Bitmap::from_range(5_000..20_000);
+    ///
+    /// lazy.repair();
+    /// assert_eq!(lazy.cardinality(), 20_000);
+    ///
+    /// lazy |= &Bitmap::of(&[100_000]);
+    /// assert_eq!(lazy.into_inner().cardinality(), 20_001);
+    /// ```
+    #[inline]
+    pub fn repair(&mut self) {
+        if !self.repaired {
+            unsafe {
+                ffi::roaring_bitmap_repair_after_lazy(&mut self.bitmap.bitmap);
+            }
+            self.repaired = true;
+        }
+    }
+
+    /// Returns the number of integers contained in the bitmap
+    ///
+    /// This does not finalize the bitmap: if lazy operations have been performed since
+    /// the last [`LazyOwnedBitmap::repair`], the cardinality is computed from a repaired
+    /// copy of the bitmap. Call `repair` first to count repeatedly without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::bitmap::LazyOwnedBitmap;
+    /// use croaring::Bitmap;
+    ///
+    /// let mut lazy = Bitmap::of(&[1, 2]).into_lazy();
+    /// lazy |= &Bitmap::of(&[2, 3]);
+    /// assert_eq!(lazy.cardinality(), 3);
+    ///
+    /// lazy |= &Bitmap::from_range(100..200);
+    /// assert_eq!(lazy.cardinality(), 103);
+    /// ```
+    #[inline]
+    pub fn cardinality(&self) -> u64 {
+        if self.repaired {
+            self.bitmap.cardinality()
+        } else {
+            self.clone().into_inner().cardinality()
         }
-        bitmap
     }
 
     #[inline]
@@ -218,28 +319,28 @@ impl LazyOwnedBitmap {
     }
 }
 
-impl std::ops::BitOrAssign<&Bitmap> for LazyOwnedBitmap {
+impl core::ops::BitOrAssign<&Bitmap> for LazyOwnedBitmap {
     #[inline]
     fn bitor_assign(&mut self, other: &Bitmap) {
         self.or_inplace(other, false);
     }
 }
 
-impl std::ops::BitOrAssign<Bitmap> for LazyOwnedBitmap {
+impl core::ops::BitOrAssign<Bitmap> for LazyOwnedBitmap {
     #[inline]
     fn bitor_assign(&mut self, mut other: Bitmap) {
         self.or_inplace_owned(&mut other, false);
     }
 }
 
-impl std::ops::BitOrAssign<&LazyOwnedBitmap> for LazyOwnedBitmap {
+impl core::ops::BitOrAssign<&LazyOwnedBitmap> for LazyOwnedBitmap {
     #[inline]
     fn bitor_assign(&mut self, other: &LazyOwnedBitmap) {
         self.or_inplace(&other.bitmap, false);
     }
 }
 
-impl std::ops::BitOrAssign<LazyOwnedBitmap> for LazyOwnedBitmap {
+impl core::ops::BitOrAssign<LazyOwnedBitmap> for LazyOwnedBitmap {
     #[inline]
     fn bitor_assign(&mut self, mut other: LazyOwnedBitmap) {
         self.or_inplace_owned(&mut other.bitmap, false);
@@ -258,6 +359,7 @@ impl<'a, 'b> BitAnd<&'a LazyOwnedBitmap> for &'b LazyOwnedBitmap {
 impl SubAssign<&Bitmap> for LazyOwnedBitmap {
     #[inline]
     fn sub_assign(&mut self, other: &Bitmap) {
+        self.repaired = false;
         unsafe { ffi::roaring_bitmap_lazy_andnot_inplace(&mut self.bitmap.bitmap, &other.bitmap) }
     }
 }
@@ -359,4 +461,82 @@ mod tests {
         let result = &bitmap1 & &bitmap2;
         assert_eq!(lazy_result, result);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_lazy_batch_or_andnot() {
+        let bitmaps_to_or = [Bitmap::of(&[1, 2, 5, 10]), Bitmap::from_range(200000..300000), Bitmap::of(&[10001, 10030, 10100]), Bitmap::from_range(2000000..3000000)];
+        let bitmaps_to_sub = [Bitmap::of(&[5]), Bitmap::from_range(210000..290000), Bitmap::of(&[10001, 11000, 11001]), Bitmap::of(&[2])];
+
+        let mut bitmap = Bitmap::of(&[99]);
+        bitmap.lazy_batch(|lazy| {
+            for (to_or, to_sub) in bitmaps_to_or.iter().zip(&bitmaps_to_sub) {
+                *lazy |= to_or;
+                *lazy -= to_sub;
+            }
+            lazy.or_inplace(&Bitmap::of(&[5]), true).andnot_inplace(&Bitmap::from_range(2500000..2600000));
+        });
+
+        let mut bitmap2 = Bitmap::of(&[99]);
+        for (to_or, to_sub) in bitmaps_to_or.iter().zip(&bitmaps_to_sub) {
+            bitmap2 |= to_or;
+            bitmap2 -= to_sub;
+        }
+        bitmap2 |= &Bitmap::of(&[5]);
+        bitmap2 -= &Bitmap::from_range(2500000..2600000);
+
+        assert_eq!(bitmap, bitmap2);
+        assert_eq!(bitmap.cardinality(), bitmap2.cardinality());
+    }
+
+    #[test]
+    fn test_lazy_batch_or_and() {
+        let bitmaps_to_or = [Bitmap::of(&[1, 2, 5, 10]), Bitmap::from_range(200000..300000), Bitmap::of(&[10001, 10030, 10100]), Bitmap::from_range(2000000..3000000)];
+        let bitmaps_to_and = [Bitmap::from_range(0..2500000), Bitmap::of(&[2, 5, 10001, 250000, 2400000]).or(&Bitmap::from_range(2300000..2600000))];
+
+        let mut bitmap = Bitmap::of(&[99]);
+        bitmap.lazy_batch(|lazy| {
+            for b in &bitmaps_to_or {
+                lazy.or_inplace(b, true);
+            }
+            *lazy &= &bitmaps_to_and[0];
+            *lazy ^= &Bitmap::of(&[7, 2300000]);
+            lazy.and_inplace(&bitmaps_to_and[1]);
+            *lazy |= &Bitmap::of(&[3]);
+        });
+
+        let mut bitmap2 = Bitmap::of(&[99]);
+        for b in &bitmaps_to_or {
+            bitmap2 |= b;
+        }
+        bitmap2 &= &bitmaps_to_and[0];
+        bitmap2 ^= &Bitmap::of(&[7, 2300000]);
+        bitmap2 &= &bitmaps_to_and[1];
+        bitmap2 |= &Bitmap::of(&[3]);
+
+        assert_eq!(bitmap, bitmap2);
+        assert_eq!(bitmap.cardinality(), bitmap2.cardinality());
+    }
+
+    #[test]
+    fn test_lazy_owned_cardinality() {
+        let bitmaps_to_or = [Bitmap::of(&[99]), Bitmap::of(&[1, 2, 5, 10]), Bitmap::from_range(200000..300000), Bitmap::from_range(250000..350000), Bitmap::of(&[10001, 10030, 10100])];
+
+        let mut lazy = LazyOwnedBitmap::create();
+        let mut expected = Bitmap::create();
+        assert_eq!(lazy.cardinality(), 0);
+        for b in &bitmaps_to_or {
+            lazy.or_inplace(b, true);
+            expected |= b;
+            assert_eq!(lazy.cardinality(), expected.cardinality());
+        }
+
+        lazy.repair();
+        assert_eq!(lazy.cardinality(), expected.cardinality());
+        assert_eq!(lazy.cardinality(), expected.cardinality());
+
+        lazy -= &Bitmap::from_range(0..260000);
+        expected -= &Bitmap::from_range(0..260000);
+        assert_eq!(lazy.cardinality(), expected.cardinality());
+        assert_eq!(lazy.into_inner(), expected);
+    }
+}
@@ -53,6 +53,7 @@
 //! println!("{:?}", rb4);
 //! ```
 
+#[repr(transparent)]
 pub struct Bitmap {
     bitmap: ffi::roaring_bitmap_t,
 }
@@ -60,14 +61,75 @@ pub struct Bitmap {
 unsafe impl Sync for Bitmap {}
 unsafe impl Send for Bitmap {}
 
-pub type Statistics = ffi::roaring_statistics_s;
+/// Statistics about the composition of a roaring bitmap, as returned by [`Bitmap::statistics`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Statistics {
+    /// Number of containers
+    pub n_containers: u32,
+    /// Number of array containers
+    pub n_array_containers: u32,
+    /// Number of run containers
+    pub n_run_containers: u32,
+    /// Number of bitset containers
+    pub n_bitset_containers: u32,
+    /// Number of values in array containers
+    pub n_values_array_containers: u32,
+    /// Number of values in run containers
+    pub n_values_run_containers: u32,
+    /// Number of values in bitset containers
+    pub n_values_bitset_containers: u32,
+    /// Number of allocated bytes in array containers
+    pub n_bytes_array_containers: u32,
+    /// Number of allocated bytes in run containers
+    pub n_bytes_run_containers: u32,
+    /// Number of allocated bytes in bitset containers
+    pub n_bytes_bitset_containers: u32,
+    /// The maximal value, undefined if cardinality is zero
+    pub max_value: u32,
+    /// The minimal value, undefined if cardinality is zero
+    pub min_value: u32,
+    /// The sum of all values (could be used to compute average)
+    pub sum_value: u64,
+    /// Total number of values stored in the bitmap
+    pub cardinality: u64,
+}
+
+/// Error returned by the fallible bitmap operations, such as [`Bitmap::try_add`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitmapError {
+    /// CRoaring could not allocate memory
+    AllocationFailed,
+}
+
+impl core::fmt::Display for BitmapError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            BitmapError::AllocationFailed => f.write_str("roaring bitmap allocation failed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BitmapError {}
 
 mod imp;
 mod iter;
 mod ops;
 mod lazy;
+mod serialization;
+mod view;
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "rayon")]
+mod rayon;
 
 pub use crate::bitmap::lazy::LazyBitmap;
 pub use crate::bitmap::lazy::LazyOwnedBitmap;
 pub use crate::bitmap::iter::BitmapIterator;
 pub use crate::bitmap::iter::BitmapIntoIterator;
+pub use crate::bitmap::iter::RunIterator;
+pub use crate::bitmap::iter::ContainerIterator;
+pub use crate::bitmap::iter::MergeIterator;
+pub use crate::bitmap::iter::IntersectIterator;
+pub use crate::bitmap::serialization::{DeserializeError, Deserializer, Frozen, Native, Portable, Serializer};
+pub use crate::bitmap::view::{FrozenBitmapView, FrozenViewError};
@@ -1,5 +1,7 @@
-use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
-use std::fmt;
+use alloc::vec::Vec;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
+use core::cmp::Ordering;
+use core::fmt;
 
 use super::Bitmap;
 
@@ -19,16 +21,176 @@ impl fmt::Debug for Bitmap {
     }
 }
 
+impl fmt::Display for Bitmap {
+    /// Formats the bitmap in set notation
+    ///
+    /// Bitmaps with more than 16 values are summarized by their first 8 values
+    /// and their cardinality.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// assert_eq!(Bitmap::create().to_string(), "{}");
+    /// assert_eq!(Bitmap::of(&[1, 2, 3]).to_string(), "{1,2,3}");
+    ///
+    /// let bitmap: Bitmap = (0..16).collect();
+    /// assert_eq!(bitmap.to_string(), "{0,1,2,3,4,5,6,7,8,9,10,11,12,13,14,15}");
+    ///
+    /// let bitmap: Bitmap = (0..100_000).collect();
+    /// assert_eq!(bitmap.to_string(), "{0,1,2,3,4,5,6,7,...} (100000 values)");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const FULL_LISTING_MAX: u64 = 16;
+        const SUMMARY_VALUES: usize = 8;
+
+        let cardinality = self.cardinality();
+        let shown = if cardinality <= FULL_LISTING_MAX {
+            cardinality as usize
+        } else {
+            SUMMARY_VALUES
+        };
+
+        f.write_str("{")?;
+        for (i, value) in self.iter().take(shown).enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}", value)?;
+        }
+        if cardinality > FULL_LISTING_MAX {
+            write!(f, ",...}} ({} values)", cardinality)
+        } else {
+            f.write_str("}")
+        }
+    }
+}
+
 impl Default for Bitmap {
+    /// Creates an empty bitmap, the same as [`Bitmap::create`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// #[derive(Default)]
+    /// struct Index {
+    ///     ids: Bitmap,
+    /// }
+    ///
+    /// assert!(Bitmap::default().is_empty());
+    /// assert!(Index::default().ids.is_empty());
+    /// ```
     fn default() -> Self {
         Self::create()
     }
 }
 
 impl PartialEq for Bitmap {
+    /// Compares the bitmaps container by container, see [`Bitmap::equals`]
     #[inline]
     fn eq(&self, other: &Bitmap) -> bool {
-        unsafe { ffi::roaring_bitmap_equals(&self.bitmap, &other.bitmap) }
+        self.equals(other)
+    }
+}
+
+impl Eq for Bitmap {}
+
+impl PartialEq<[u32]> for Bitmap {
+    /// Compares the bitmap's elements in ascending order with a slice
+    ///
+    /// A slice which is not strictly ascending (unsorted, or containing duplicates)
+    /// is never equal to a bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, 3]);
+    ///
+    /// assert_eq!(bitmap, [1, 2, 3][..]);
+    /// assert_ne!(bitmap, [1, 2][..]);
+    /// assert_ne!(bitmap, [3, 2, 1][..]);
+    /// assert_ne!(bitmap, [1, 1, 2, 3][..]);
+    /// ```
+    fn eq(&self, other: &[u32]) -> bool {
+        other.len() as u64 == self.cardinality() && self.iter().eq(other.iter().copied())
+    }
+}
+
+impl PartialEq<Vec<u32>> for Bitmap {
+    /// Compares the bitmap's elements in ascending order with a vector
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, 3]);
+    ///
+    /// assert_eq!(bitmap, vec![1, 2, 3]);
+    /// assert_ne!(bitmap, vec![1, 3, 2]);
+    /// assert_ne!(Bitmap::create(), vec![0]);
+    /// assert_eq!(Bitmap::create(), Vec::<u32>::new());
+    /// ```
+    #[inline]
+    fn eq(&self, other: &Vec<u32>) -> bool {
+        *self == other[..]
+    }
+}
+
+impl<const N: usize> PartialEq<[u32; N]> for Bitmap {
+    /// Compares the bitmap's elements in ascending order with an array
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, 3]);
+    ///
+    /// assert_eq!(bitmap, [1, 2, 3]);
+    /// assert_ne!(bitmap, [1, 2, 3, 3]);
+    /// assert_ne!(bitmap, [1, 2, 4]);
+    /// ```
+    #[inline]
+    fn eq(&self, other: &[u32; N]) -> bool {
+        *self == other[..]
+    }
+}
+
+impl PartialOrd for Bitmap {
+    #[inline]
+    fn partial_cmp(&self, other: &Bitmap) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Bitmap {
+    /// Compares bitmaps lexicographically by their elements in ascending order
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap1 = Bitmap::of(&[1, 2]);
+    /// let bitmap2 = Bitmap::of(&[1, 2, 3]);
+    /// let bitmap3 = Bitmap::of(&[1, 3]);
+    ///
+    /// assert!(Bitmap::create() < bitmap1);
+    /// assert!(bitmap1 < bitmap2);
+    /// assert!(bitmap2 < bitmap3);
+    ///
+    /// let mut bitmaps = vec![bitmap3.clone(), bitmap1.clone(), bitmap2.clone()];
+    /// bitmaps.sort();
+    /// assert_eq!(bitmaps, [bitmap1, bitmap2, bitmap3]);
+    /// ```
+    fn cmp(&self, other: &Bitmap) -> Ordering {
+        self.iter().cmp(other.iter())
     }
 }
 
@@ -53,6 +215,24 @@ impl Clone for Bitmap {
         result
     }
 
+    /// Overwrite this bitmap with a copy of `source`
+    ///
+    /// Reuses the existing allocation where possible, rather than allocating
+    /// a new bitmap and freeing the old one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::create();
+    ///
+    /// for i in 1..5 {
+    ///     let source: Bitmap = (0..i * 1000).collect();
+    ///     bitmap.clone_from(&source);
+    ///     assert_eq!(bitmap, source);
+    /// }
+    /// ```
     fn clone_from(&mut self, source: &Self) {
         unsafe {
             let success = ffi::roaring_bitmap_overwrite(&mut self.bitmap, &source.bitmap);
@@ -75,8 +255,8 @@ impl Drop for Bitmap {
         // > By contract, calling roaring_bitmap_clear() is enough to
         // > release all auxiliary memory used by the structure.
         //
-        // We do not currently expose a way to get a frozen bitmap, but if we ever do,
-        // look at the roaring.hh destructor for implementation
+        // Frozen bitmaps are only exposed through FrozenBitmapView, which is never
+        // dropped as a Bitmap, see the roaring.hh destructor for how they are freed
         unsafe { ffi::roaring_bitmap_clear(&mut self.bitmap) }
     }
 }
@@ -86,6 +266,8 @@ impl BitAnd for Bitmap {
 
     /// Syntactic sugar for `.and`
     ///
+    /// Rather than allocating a new bitmap, the result is computed in place with `.and_inplace_owned`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -104,8 +286,9 @@ impl BitAnd for Bitmap {
     /// assert!(!bitmap3.contains(2));
     /// ```
     #[inline]
-    fn bitand(self, other: Bitmap) -> Bitmap {
-        self.and(&other)
+    fn bitand(mut self, other: Bitmap) -> Bitmap {
+        self.and_inplace_owned(other);
+        self
     }
 }
 
@@ -114,6 +297,8 @@ impl<'a> BitAnd<&'a Bitmap> for Bitmap {
 
     /// Syntactic sugar for `.and`
     ///
+    /// Rather than allocating a new bitmap, the result is computed in place in `self`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -128,8 +313,9 @@ impl<'a> BitAnd<&'a Bitmap> for Bitmap {
     /// assert!(!bitmap3.contains(2));
     /// ```
     #[inline]
-    fn bitand(self, other: &'a Bitmap) -> Bitmap {
-        self.and(other)
+    fn bitand(mut self, other: &'a Bitmap) -> Bitmap {
+        self.and_inplace(other);
+        self
     }
 }
 
@@ -157,8 +343,35 @@ impl<'a, 'b> BitAnd<&'a Bitmap> for &'b Bitmap {
     }
 }
 
+impl<'a> BitAnd<Bitmap> for &'a Bitmap {
+    type Output = Bitmap;
+
+    /// Syntactic sugar for `.and`
+    ///
+    /// Rather than allocating a new bitmap, the result is computed in place in `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap1 = Bitmap::of(&[1]);
+    /// let bitmap2 = Bitmap::of(&[1, 2]);
+    ///
+    /// let bitmap3 = &bitmap1 & bitmap2;
+    ///
+    /// assert!(bitmap3.contains(1));
+    /// assert!(!bitmap3.contains(2));
+    /// ```
+    #[inline]
+    fn bitand(self, mut other: Bitmap) -> Bitmap {
+        other.and_inplace(self);
+        other
+    }
+}
+
 impl BitAndAssign for Bitmap {
-    /// Syntactic sugar for `.and_inplace`
+    /// Syntactic sugar for `.and_inplace_owned`
     ///
     /// # Examples
     ///
@@ -184,7 +397,7 @@ impl BitAndAssign for Bitmap {
     /// ```
     #[inline]
     fn bitand_assign(&mut self, other: Bitmap) {
-        self.and_inplace(&other);
+        self.and_inplace_owned(other);
     }
 }
 
@@ -224,6 +437,8 @@ impl BitOr for Bitmap {
 
     /// Syntatic sugar for `.or`
     ///
+    /// Rather than allocating a new bitmap, the result is computed in place with `.or_inplace_owned`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -239,8 +454,9 @@ impl BitOr for Bitmap {
     /// assert!(bitmap3.contains(25));
     /// ```
     #[inline]
-    fn bitor(self, other: Bitmap) -> Bitmap {
-        self.or(&other)
+    fn bitor(mut self, other: Bitmap) -> Bitmap {
+        self.or_inplace_owned(other);
+        self
     }
 }
 
@@ -249,6 +465,8 @@ impl<'a> BitOr<&'a Bitmap> for Bitmap {
 
     /// Syntatic sugar for `.or`
     ///
+    /// Rather than allocating a new bitmap, the result is computed in place in `self`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -264,8 +482,9 @@ impl<'a> BitOr<&'a Bitmap> for Bitmap {
     /// assert!(bitmap3.contains(25));
     /// ```
     #[inline]
-    fn bitor(self, other: &'a Bitmap) -> Bitmap {
-        self.or(other)
+    fn bitor(mut self, other: &'a Bitmap) -> Bitmap {
+        self.or_inplace(other);
+        self
     }
 }
 
@@ -297,8 +516,39 @@ impl<'a, 'b> BitOr<&'a Bitmap> for &'b Bitmap {
     }
 }
 
+impl<'a> BitOr<Bitmap> for &'a Bitmap {
+    type Output = Bitmap;
+
+    /// Syntatic sugar for `.or`
+    ///
+    /// Rather than allocating a new bitmap, the result is computed in place in `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let mut bitmap1 = Bitmap::create();
+    /// bitmap1.add(15);
+    ///
+    /// let mut bitmap2 = Bitmap::create();
+    /// bitmap2.add(25);
+    ///
+    /// let bitmap3 = &bitmap1 | bitmap2;
+    ///
+    /// assert!(bitmap3.cardinality() == 2);
+    /// assert!(bitmap3.contains(15));
+    /// assert!(bitmap3.contains(25));
+    /// ```
+    #[inline]
+    fn bitor(self, mut other: Bitmap) -> Bitmap {
+        other.or_inplace(self);
+        other
+    }
+}
+
 impl BitOrAssign for Bitmap {
-    /// Syntatic sugar for `.or_inplace`
+    /// Syntatic sugar for `.or_inplace_owned`
     ///
     /// # Examples
     ///
@@ -316,7 +566,7 @@ impl BitOrAssign for Bitmap {
     /// ```
     #[inline]
     fn bitor_assign(&mut self, other: Bitmap) {
-        self.or_inplace(&other)
+        self.or_inplace_owned(other);
     }
 }
 
@@ -348,6 +598,8 @@ impl BitXor for Bitmap {
 
     /// Syntatic sugar for `.xor`
     ///
+    /// Rather than allocating a new bitmap, the result is computed in place with `.xor_inplace_owned`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -364,8 +616,9 @@ impl BitXor for Bitmap {
     /// assert!(bitmap3.contains(35));
     /// ```
     #[inline]
-    fn bitxor(self, other: Bitmap) -> Bitmap {
-        self.xor(&other)
+    fn bitxor(mut self, other: Bitmap) -> Bitmap {
+        self.xor_inplace_owned(other);
+        self
     }
 }
 
@@ -374,6 +627,8 @@ impl<'a> BitXor<&'a Bitmap> for Bitmap {
 
     /// Syntatic sugar for `.xor`
     ///
+    /// Rather than allocating a new bitmap, the result is computed in place in `self`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -390,8 +645,9 @@ impl<'a> BitXor<&'a Bitmap> for Bitmap {
     /// assert!(bitmap3.contains(35));
     /// ```
     #[inline]
-    fn bitxor(self, other: &'a Bitmap) -> Bitmap {
-        self.xor(other)
+    fn bitxor(mut self, other: &'a Bitmap) -> Bitmap {
+        self.xor_inplace(other);
+        self
     }
 }
 
@@ -421,8 +677,37 @@ impl<'a, 'b> BitXor<&'a Bitmap> for &'b Bitmap {
     }
 }
 
+impl<'a> BitXor<Bitmap> for &'a Bitmap {
+    type Output = Bitmap;
+
+    /// Syntatic sugar for `.xor`
+    ///
+    /// Rather than allocating a new bitmap, the result is computed in place in `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap1 = Bitmap::of(&[15, 25]);
+    /// let bitmap2 = Bitmap::of(&[25, 35]);
+    ///
+    /// let bitmap3 = &bitmap1 ^ bitmap2;
+    ///
+    /// assert!(bitmap3.cardinality() == 2);
+    /// assert!(bitmap3.contains(15));
+    /// assert!(!bitmap3.contains(25));
+    /// assert!(bitmap3.contains(35));
+    /// ```
+    #[inline]
+    fn bitxor(self, mut other: Bitmap) -> Bitmap {
+        other.xor_inplace(self);
+        other
+    }
+}
+
 impl BitXorAssign for Bitmap {
-    /// Syntatic sugar for `.xor_inplace`
+    /// Syntatic sugar for `.xor_inplace_owned`
     ///
     /// # Examples
     ///
@@ -441,7 +726,7 @@ impl BitXorAssign for Bitmap {
     /// ```
     #[inline]
     fn bitxor_assign(&mut self, other: Bitmap) {
-        self.xor_inplace(&other)
+        self.xor_inplace_owned(other);
     }
 }
 
@@ -474,6 +759,8 @@ impl Sub for Bitmap {
 
     /// Syntatic sugar for `.andnot`
     ///
+    /// Rather than allocating a new bitmap, the result is computed in place in `self`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -490,8 +777,9 @@ impl Sub for Bitmap {
     /// assert!(!bitmap3.contains(35));
     /// ```
     #[inline]
-    fn sub(self, other: Bitmap) -> Bitmap {
-        self.andnot(&other)
+    fn sub(mut self, other: Bitmap) -> Bitmap {
+        self.andnot_inplace(&other);
+        self
     }
 }
 
@@ -500,6 +788,8 @@ impl<'a> Sub<&'a Bitmap> for Bitmap {
 
     /// Syntatic sugar for `.andnot`
     ///
+    /// Rather than allocating a new bitmap, the result is computed in place in `self`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -516,8 +806,9 @@ impl<'a> Sub<&'a Bitmap> for Bitmap {
     /// assert!(!bitmap3.contains(35));
     /// ```
     #[inline]
-    fn sub(self, other: &'a Bitmap) -> Bitmap {
-        self.andnot(other)
+    fn sub(mut self, other: &'a Bitmap) -> Bitmap {
+        self.andnot_inplace(other);
+        self
     }
 }
 
@@ -547,6 +838,32 @@ impl<'a, 'b> Sub<&'a Bitmap> for &'b Bitmap {
     }
 }
 
+impl<'a> Sub<Bitmap> for &'a Bitmap {
+    type Output = Bitmap;
+
+    /// Syntatic sugar for `.andnot`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap1 = Bitmap::of(&[15, 25]);
+    /// let bitmap2 = Bitmap::of(&[25, 35]);
+    ///
+    /// let bitmap3 = &bitmap1 - bitmap2;
+    ///
+    /// assert_eq!(bitmap3.cardinality(), 1);
+    /// assert!(bitmap3.contains(15));
+    /// assert!(!bitmap3.contains(25));
+    /// assert!(!bitmap3.contains(35));
+    /// ```
+    #[inline]
+    fn sub(self, other: Bitmap) -> Bitmap {
+        self.andnot(&other)
+    }
+}
+
 impl SubAssign for Bitmap {
     /// Syntatic sugar for `.andnot_inplace`
     ///
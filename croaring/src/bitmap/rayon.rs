@@ -0,0 +1,45 @@
+use alloc::vec::Vec;
+
+use super::Bitmap;
+
+/// Below this many bitmaps, a subrange is unioned sequentially with [`Bitmap::fast_or`]
+const SEQUENTIAL_THRESHOLD: usize = 64;
+
+impl Bitmap {
+    /// Computes the union of many bitmaps in parallel using rayon's global
+    /// thread pool. Returns the result as a new bitmap.
+    ///
+    /// The slice is split recursively, each half is unioned in parallel, and
+    /// the partial results are combined. The result is always the same as
+    /// [`Bitmap::fast_or`], which is usually faster for a small number of bitmaps.
+    ///
+    /// The union of an empty slice is an empty bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmaps: Vec<Bitmap> = (0..1000).map(|i| Bitmap::of(&[i, i * 100_000])).collect();
+    ///
+    /// let union = Bitmap::par_fast_or(&bitmaps);
+    /// let refs: Vec<&Bitmap> = bitmaps.iter().collect();
+    ///
+    /// assert_eq!(union, Bitmap::fast_or(&refs));
+    /// assert_eq!(union.cardinality(), 1999);
+    ///
+    /// assert!(Bitmap::par_fast_or(&[]).is_empty());
+    /// ```
+    pub fn par_fast_or(bitmaps: &[Bitmap]) -> Self {
+        if bitmaps.len() <= SEQUENTIAL_THRESHOLD {
+            let refs: Vec<&Bitmap> = bitmaps.iter().collect();
+            return Self::fast_or(&refs);
+        }
+
+        let (left, right) = bitmaps.split_at(bitmaps.len() / 2);
+        let (mut left, right) =
+            rayon::join(|| Self::par_fast_or(left), || Self::par_fast_or(right));
+        left.or_inplace_owned(right);
+        left
+    }
+}
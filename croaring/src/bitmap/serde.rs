@@ -0,0 +1,91 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use super::{Bitmap, Portable};
+
+impl Serialize for Bitmap {
+    /// Serializes the bitmap using the portable format.
+    ///
+    /// Human readable formats (e.g. JSON) receive the portable bytes as a
+    /// base64 encoded string, other formats receive the raw bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, 3]);
+    ///
+    /// let json = serde_json::to_string(&bitmap).unwrap();
+    /// let deserialized: Bitmap = serde_json::from_str(&json).unwrap();
+    ///
+    /// assert_eq!(bitmap, deserialized);
+    /// ```
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let buffer = self.serialize::<Portable>();
+
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&BASE64.encode(buffer))
+        } else {
+            serializer.serialize_bytes(&buffer)
+        }
+    }
+}
+
+struct BitmapVisitor;
+
+impl<'de> Visitor<'de> for BitmapVisitor {
+    type Value = Bitmap;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a portable serialized roaring bitmap")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Bitmap, E> {
+        Bitmap::try_deserialize::<Portable>(bytes)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Bytes(bytes), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Bitmap, E> {
+        let bytes = BASE64
+            .decode(s)
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(s), &"a base64 encoded string"))?;
+        self.visit_bytes(&bytes)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Bitmap, A::Error> {
+        // The length comes from the input, so don't trust it for more than a small allocation
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bitmap {
+    /// Deserializes a bitmap from the portable format, as written by `Serialize`.
+    ///
+    /// Invalid input results in an error, rather than a panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Bitmap;
+    ///
+    /// let result: Result<Bitmap, _> = serde_json::from_str("\"AQID\"");
+    /// assert!(result.is_err());
+    /// ```
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BitmapVisitor)
+        } else {
+            deserializer.deserialize_bytes(BitmapVisitor)
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::{TryFrom, TryInto};
+use core::fmt;
+use core::slice;
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+
+use super::Bitmap;
+
+/// Trait for different formats of bitmap serialization
+pub trait Serializer {
+    /// Serializes `bitmap`, appending the bytes to `dst`
+    fn serialize_into(bitmap: &Bitmap, dst: &mut Vec<u8>);
+
+    /// Returns the number of bytes `serialize_into` appends for `bitmap`
+    fn get_serialized_size_in_bytes(bitmap: &Bitmap) -> usize;
+}
+
+/// Trait for different formats of bitmap deserialization
+pub trait Deserializer {
+    /// Deserializes a bitmap from `buffer`, returning None if the buffer does not
+    /// contain a valid bitmap in this format
+    fn try_deserialize(buffer: &[u8]) -> Option<Bitmap>;
+}
+
+/// The portable format, compatible with the Java and Go implementations
+///
+/// See <https://github.com/RoaringBitmap/RoaringFormatSpec>
+pub enum Portable {}
+
+/// The native CRoaring format
+///
+/// Not compatible with other implementations, but can be more compact than
+/// [`Portable`] for very sparse bitmaps. Values are stored in native byte order.
+pub enum Native {}
+
+/// The frozen CRoaring format
+///
+/// Allows a bitmap to be viewed in place, without copying, from a buffer aligned
+/// to 32 bytes. Not compatible with other implementations.
+pub enum Frozen {}
+
+impl Serializer for Portable {
+    fn serialize_into(bitmap: &Bitmap, dst: &mut Vec<u8>) {
+        let len = Self::get_serialized_size_in_bytes(bitmap);
+        dst.reserve(len);
+        let offset = dst.len();
+
+        unsafe {
+            ffi::roaring_bitmap_portable_serialize(
+                &bitmap.bitmap,
+                dst.as_mut_ptr().add(offset) as *mut ::libc::c_char,
+            );
+            dst.set_len(offset + len);
+        }
+    }
+
+    fn get_serialized_size_in_bytes(bitmap: &Bitmap) -> usize {
+        unsafe {
+            ffi::roaring_bitmap_portable_size_in_bytes(&bitmap.bitmap)
+                .try_into()
+                .unwrap()
+        }
+    }
+}
+
+impl Deserializer for Portable {
+    fn try_deserialize(buffer: &[u8]) -> Option<Bitmap> {
+        unsafe {
+            let bitmap = ffi::roaring_bitmap_portable_deserialize_safe(
+                buffer.as_ptr() as *const ::libc::c_char,
+                buffer.len().try_into().unwrap(),
+            );
+
+            if !bitmap.is_null() {
+                Some(Bitmap::take_heap(bitmap))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Error returned when converting bytes which don't contain a valid bitmap in the
+/// [`Portable`] format into a [`Bitmap`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeserializeError {
+    /// The bytes end before the end of the bitmap described by their headers
+    Truncated,
+    /// The bytes don't describe a valid bitmap
+    Invalid,
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeserializeError::Truncated => f.write_str("truncated portable roaring bitmap"),
+            DeserializeError::Invalid => f.write_str("invalid portable roaring bitmap"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for DeserializeError {}
+
+impl TryFrom<&[u8]> for Bitmap {
+    type Error = DeserializeError;
+
+    /// Deserializes a bitmap from bytes in the [`Portable`] format
+    ///
+    /// Like [`Bitmap::try_deserialize`], any bytes after the end of the bitmap are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, DeserializeError, Portable};
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, 100_000]);
+    /// let serialized = bitmap.serialize::<Portable>();
+    ///
+    /// let deserialized: Bitmap = serialized[..].try_into().unwrap();
+    /// assert_eq!(deserialized, bitmap);
+    ///
+    /// let truncated = &serialized[..serialized.len() - 1];
+    /// assert_eq!(Bitmap::try_from(truncated), Err(DeserializeError::Truncated));
+    /// assert_eq!(Bitmap::try_from(&b"garbage"[..]), Err(DeserializeError::Invalid));
+    /// ```
+    fn try_from(buffer: &[u8]) -> Result<Self, DeserializeError> {
+        if let Some(bitmap) = Portable::try_deserialize(buffer) {
+            return Ok(bitmap);
+        }
+
+        // Re-read the headers to report why the bytes were rejected
+        let mut remaining = buffer;
+        let result = read_portable_with(
+            |read, len| {
+                if remaining.len() < len {
+                    return Err(DeserializeError::Truncated);
+                }
+                let (bytes, rest) = remaining.split_at(len);
+                remaining = rest;
+                let start = read.len();
+                read.extend_from_slice(bytes);
+                Ok(start)
+            },
+            |_| DeserializeError::Invalid,
+        );
+        // Headers which describe a complete bitmap were still rejected by CRoaring
+        Err(result.err().unwrap_or(DeserializeError::Invalid))
+    }
+}
+
+// Constants from the portable format specification
+const SERIAL_COOKIE_NO_RUNCONTAINER: u32 = 12346;
+const SERIAL_COOKIE: u32 = 12347;
+const NO_OFFSET_THRESHOLD: usize = 4;
+const MAX_ARRAY_CARDINALITY: usize = 4096;
+const BITSET_CONTAINER_BYTES: usize = 8192;
+
+/// Reads exactly the bytes of one bitmap in the [`Portable`] format from `reader`,
+/// using the headers to work out how long the bitmap is.
+#[cfg(feature = "std")]
+pub(crate) fn read_portable<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    read_portable_with(
+        |buffer, len| {
+            let start = buffer.len();
+            buffer.resize(start + len, 0);
+            reader.read_exact(&mut buffer[start..])?;
+            Ok(start)
+        },
+        |message| io::Error::new(io::ErrorKind::InvalidData, message),
+    )
+}
+
+/// Reads the bytes of one bitmap in the [`Portable`] format, using the headers to
+/// work out how long the bitmap is.
+///
+/// `read_more(buffer, len)` appends exactly `len` more bytes to `buffer`, returning
+/// the offset they start at, and `invalid` creates the error for invalid headers.
+fn read_portable_with<E>(
+    mut read_more: impl FnMut(&mut Vec<u8>, usize) -> Result<usize, E>,
+    invalid: impl Fn(&'static str) -> E,
+) -> Result<Vec<u8>, E> {
+    let mut buffer = Vec::new();
+    read_more(&mut buffer, 4)?;
+    let cookie = u32::from_le_bytes(buffer[..4].try_into().unwrap());
+
+    let (size, run_flags) = if cookie & 0xFFFF == SERIAL_COOKIE {
+        let size = (cookie >> 16) as usize + 1;
+        let start = read_more(&mut buffer, (size + 7) / 8)?;
+        (size, Some(start))
+    } else if cookie == SERIAL_COOKIE_NO_RUNCONTAINER {
+        let start = read_more(&mut buffer, 4)?;
+        let size = u32::from_le_bytes(buffer[start..].try_into().unwrap()) as usize;
+        if size > 1 << 16 {
+            return Err(invalid("too many containers"));
+        }
+        (size, None)
+    } else {
+        return Err(invalid("invalid cookie"));
+    };
+
+    let header = read_more(&mut buffer, size * 4)?;
+    if run_flags.is_none() || size >= NO_OFFSET_THRESHOLD {
+        read_more(&mut buffer, size * 4)?;
+    }
+
+    for i in 0..size {
+        let is_run = run_flags.map_or(false, |start| buffer[start + i / 8] & (1 << (i % 8)) != 0);
+        if is_run {
+            let start = read_more(&mut buffer, 2)?;
+            let n_runs = u16::from_le_bytes([buffer[start], buffer[start + 1]]) as usize;
+            read_more(&mut buffer, n_runs * 4)?;
+        } else {
+            let offset = header + i * 4 + 2;
+            let cardinality = u16::from_le_bytes([buffer[offset], buffer[offset + 1]]) as usize + 1;
+            if cardinality <= MAX_ARRAY_CARDINALITY {
+                read_more(&mut buffer, cardinality * 2)?;
+            } else {
+                read_more(&mut buffer, BITSET_CONTAINER_BYTES)?;
+            }
+        }
+    }
+
+    Ok(buffer)
+}
+
+// Type tags written by `roaring_bitmap_serialize`
+const NATIVE_ARRAY_UINT32: u8 = 1;
+const NATIVE_CONTAINER: u8 = 2;
+
+impl Serializer for Native {
+    fn serialize_into(bitmap: &Bitmap, dst: &mut Vec<u8>) {
+        let len = Self::get_serialized_size_in_bytes(bitmap);
+        dst.reserve(len);
+        let offset = dst.len();
+
+        unsafe {
+            ffi::roaring_bitmap_serialize(
+                &bitmap.bitmap,
+                dst.as_mut_ptr().add(offset) as *mut ::libc::c_char,
+            );
+            dst.set_len(offset + len);
+        }
+    }
+
+    fn get_serialized_size_in_bytes(bitmap: &Bitmap) -> usize {
+        unsafe {
+            ffi::roaring_bitmap_size_in_bytes(&bitmap.bitmap)
+                .try_into()
+                .unwrap()
+        }
+    }
+}
+
+impl Deserializer for Native {
+    // `roaring_bitmap_deserialize` does not bounds check its input, so the
+    // framing is validated here before handing the container data to CRoaring
+    fn try_deserialize(buffer: &[u8]) -> Option<Bitmap> {
+        match buffer.split_first() {
+            Some((&NATIVE_ARRAY_UINT32, rest)) => {
+                let (cardinality, values) = rest.split_at(rest.len().min(4));
+                let cardinality = u32::from_ne_bytes(cardinality.try_into().ok()?) as usize;
+                let values = values.get(..cardinality.checked_mul(4)?)?;
+
+                let values: Vec<u32> = values
+                    .chunks_exact(4)
+                    .map(|value| u32::from_ne_bytes(value.try_into().unwrap()))
+                    .collect();
+                Some(Bitmap::of(&values))
+            }
+            Some((&NATIVE_CONTAINER, rest)) => Portable::try_deserialize(rest),
+            _ => None,
+        }
+    }
+}
+
+impl Serializer for Frozen {
+    fn serialize_into(bitmap: &Bitmap, dst: &mut Vec<u8>) {
+        let len = Self::get_serialized_size_in_bytes(bitmap);
+        dst.reserve(len);
+        let offset = dst.len();
+
+        unsafe {
+            ffi::roaring_bitmap_frozen_serialize(
+                &bitmap.bitmap,
+                dst.as_mut_ptr().add(offset) as *mut ::libc::c_char,
+            );
+            dst.set_len(offset + len);
+        }
+    }
+
+    fn get_serialized_size_in_bytes(bitmap: &Bitmap) -> usize {
+        unsafe {
+            ffi::roaring_bitmap_frozen_size_in_bytes(&bitmap.bitmap)
+                .try_into()
+                .unwrap()
+        }
+    }
+}
+
+#[repr(C, align(32))]
+#[derive(Clone, Copy)]
+struct Align32([u8; 32]);
+
+impl Deserializer for Frozen {
+    // The frozen view requires a buffer aligned to 32 bytes, so the input is
+    // copied into an aligned buffer and the view is then copied into an owned bitmap
+    fn try_deserialize(buffer: &[u8]) -> Option<Bitmap> {
+        let mut aligned = vec![Align32([0; 32]); (buffer.len() + 31) / 32];
+        let aligned_bytes =
+            unsafe { slice::from_raw_parts_mut(aligned.as_mut_ptr() as *mut u8, buffer.len()) };
+        aligned_bytes.copy_from_slice(buffer);
+
+        unsafe {
+            let view = ffi::roaring_bitmap_frozen_view(
+                aligned.as_ptr() as *const ::libc::c_char,
+                buffer.len().try_into().unwrap(),
+            );
+            if view.is_null() {
+                return None;
+            }
+
+            let bitmap = ffi::roaring_bitmap_copy(view);
+            ffi::roaring_bitmap_free(view);
+
+            if !bitmap.is_null() {
+                Some(Bitmap::take_heap(bitmap))
+            } else {
+                None
+            }
+        }
+    }
+}
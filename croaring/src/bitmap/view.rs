@@ -0,0 +1,176 @@
+use core::convert::{TryFrom, TryInto};
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::ptr::NonNull;
+
+use ffi::roaring_bitmap_t;
+
+use super::Bitmap;
+
+/// A read-only bitmap viewed in place over a buffer in the [`Frozen`](super::Frozen) format
+///
+/// The view borrows the buffer rather than copying its containers, which makes it
+/// suitable for memory-mapped data. All read-only [`Bitmap`] methods are available
+/// through `Deref`, and the view can't outlive the buffer it was created from.
+///
+/// # Examples
+///
+/// ```
+/// use croaring::{Bitmap, Frozen, FrozenBitmapView};
+///
+/// let bitmap: Bitmap = (1..5).chain(100_000..200_000).collect();
+/// let serialized = bitmap.serialize::<Frozen>();
+///
+/// // The view requires the data to be aligned to 32 bytes
+/// let mut buffer = vec![0; serialized.len() + 31];
+/// let offset = buffer.as_ptr().align_offset(32);
+/// buffer[offset..offset + serialized.len()].copy_from_slice(&serialized);
+/// let data = &buffer[offset..offset + serialized.len()];
+///
+/// let view = FrozenBitmapView::try_deserialize(data).unwrap();
+///
+/// assert!(view.contains(3));
+/// assert_eq!(view.cardinality(), bitmap.cardinality());
+/// assert!(view.iter().eq(bitmap.iter()));
+/// assert_eq!(view.and_cardinality(&Bitmap::of(&[1, 2, 10])), 2);
+/// assert_eq!(*view, bitmap);
+/// ```
+pub struct FrozenBitmapView<'a> {
+    bitmap: NonNull<roaring_bitmap_t>,
+    phantom: PhantomData<&'a [u8]>,
+}
+
+unsafe impl<'a> Sync for FrozenBitmapView<'a> {}
+unsafe impl<'a> Send for FrozenBitmapView<'a> {}
+
+impl<'a> FrozenBitmapView<'a> {
+    /// Creates a view of a bitmap serialized in the [`Frozen`](super::Frozen) format
+    ///
+    /// Returns None if the buffer is not aligned to 32 bytes, or its size doesn't
+    /// match the layout described by its header.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, Frozen, FrozenBitmapView};
+    ///
+    /// let serialized = Bitmap::of(&[1, 2, 3]).serialize::<Frozen>();
+    ///
+    /// let mut buffer = vec![0; serialized.len() + 32];
+    /// let offset = buffer.as_ptr().align_offset(32);
+    ///
+    /// // Misaligned
+    /// buffer[offset + 1..offset + 1 + serialized.len()].copy_from_slice(&serialized);
+    /// assert!(FrozenBitmapView::try_deserialize(&buffer[offset + 1..offset + 1 + serialized.len()]).is_none());
+    ///
+    /// // Truncated
+    /// buffer[offset..offset + serialized.len()].copy_from_slice(&serialized);
+    /// assert!(FrozenBitmapView::try_deserialize(&buffer[offset..offset + serialized.len() - 1]).is_none());
+    ///
+    /// let view = FrozenBitmapView::try_deserialize(&buffer[offset..offset + serialized.len()]);
+    /// assert_eq!(view.unwrap().to_vec(), [1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn try_deserialize(buffer: &'a [u8]) -> Option<Self> {
+        Self::try_from(buffer).ok()
+    }
+}
+
+/// Error returned when a buffer can't be viewed as a [`FrozenBitmapView`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrozenViewError {
+    /// The buffer doesn't start on a 32 byte boundary
+    Misaligned,
+    /// The buffer's size doesn't match the layout described by its header
+    Invalid,
+}
+
+impl fmt::Display for FrozenViewError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FrozenViewError::Misaligned => f.write_str("misaligned frozen roaring bitmap"),
+            FrozenViewError::Invalid => f.write_str("invalid frozen roaring bitmap"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrozenViewError {}
+
+impl<'a> TryFrom<&'a [u8]> for FrozenBitmapView<'a> {
+    type Error = FrozenViewError;
+
+    /// Creates a view of a bitmap serialized in the [`Frozen`](super::Frozen) format
+    ///
+    /// Unlike [`FrozenBitmapView::try_deserialize`], reports why the buffer was rejected.
+    /// Use [`Bitmap::serialize_frozen_into`] to produce a correctly aligned buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, FrozenBitmapView, FrozenViewError};
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, 3]);
+    /// let mut buffer = Vec::new();
+    /// let serialized = bitmap.serialize_frozen_into(&mut buffer).to_vec();
+    ///
+    /// let mut buffer = vec![0; serialized.len() + 32];
+    /// let offset = buffer.as_ptr().align_offset(32);
+    ///
+    /// buffer[offset + 1..offset + 1 + serialized.len()].copy_from_slice(&serialized);
+    /// let misaligned = &buffer[offset + 1..offset + 1 + serialized.len()];
+    /// assert_eq!(FrozenBitmapView::try_from(misaligned).unwrap_err(), FrozenViewError::Misaligned);
+    ///
+    /// buffer[offset..offset + serialized.len()].copy_from_slice(&serialized);
+    /// let truncated = &buffer[offset..offset + serialized.len() - 1];
+    /// assert_eq!(FrozenBitmapView::try_from(truncated).unwrap_err(), FrozenViewError::Invalid);
+    ///
+    /// let view = FrozenBitmapView::try_from(&buffer[offset..offset + serialized.len()]).unwrap();
+    /// assert_eq!(*view, bitmap);
+    /// ```
+    fn try_from(buffer: &'a [u8]) -> Result<Self, FrozenViewError> {
+        if buffer.as_ptr().align_offset(32) != 0 {
+            return Err(FrozenViewError::Misaligned);
+        }
+
+        let bitmap = unsafe {
+            ffi::roaring_bitmap_frozen_view(
+                buffer.as_ptr() as *const ::libc::c_char,
+                buffer.len().try_into().unwrap(),
+            )
+        };
+
+        NonNull::new(bitmap as *mut roaring_bitmap_t)
+            .map(|bitmap| FrozenBitmapView {
+                bitmap,
+                phantom: PhantomData,
+            })
+            .ok_or(FrozenViewError::Invalid)
+    }
+}
+
+impl<'a> Deref for FrozenBitmapView<'a> {
+    type Target = Bitmap;
+
+    #[inline]
+    fn deref(&self) -> &Bitmap {
+        // Bitmap is a transparent wrapper around roaring_bitmap_t. The view is never
+        // dropped as a Bitmap, and can't be mutated through a shared reference.
+        unsafe { &*(self.bitmap.as_ptr() as *const Bitmap) }
+    }
+}
+
+impl<'a> Drop for FrozenBitmapView<'a> {
+    fn drop(&mut self) {
+        // The frozen view allocates the bitmap and its container headers in a single
+        // block, the container data is borrowed from the buffer
+        unsafe { ffi::roaring_bitmap_free(self.bitmap.as_ptr()) }
+    }
+}
+
+impl<'a> fmt::Debug for FrozenBitmapView<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -1,7 +1,20 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod bitmap;
 pub mod treemap;
 
 pub use bitmap::Bitmap;
+pub use bitmap::BitmapError;
 pub use bitmap::BitmapIterator;
 pub use bitmap::BitmapIntoIterator;
+pub use bitmap::RunIterator;
+pub use bitmap::ContainerIterator;
+pub use bitmap::MergeIterator;
+pub use bitmap::IntersectIterator;
+pub use bitmap::{DeserializeError, Frozen, Native, Portable};
+pub use bitmap::{FrozenBitmapView, FrozenViewError};
 pub use treemap::Treemap;
+pub use treemap::TreemapIntoIterator;
+pub use treemap::TreemapIterator;
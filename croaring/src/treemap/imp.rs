@@ -1,10 +1,12 @@
+use crate::bitmap::Statistics;
 use crate::Bitmap;
 use crate::Treemap;
 
 use super::util;
-use std::collections::btree_map::Entry;
-use std::collections::BTreeMap;
-use std::u64;
+use alloc::collections::btree_map::Entry;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::ops::{Bound, RangeBounds};
 
 impl Treemap {
     /// Creates an empty `Treemap`.
@@ -40,10 +42,183 @@ impl Treemap {
         self.map.entry(hi).or_insert_with(Bitmap::create).add(lo)
     }
 
+    /// Add all values in range
+    ///
+    /// The range may span any number of inner bitmaps, up to and including `u64::MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let mut treemap = Treemap::create();
+    /// treemap.add_range((1 << 32) - 2..(3 << 32) + 2);
+    ///
+    /// assert_eq!(treemap.cardinality(), (2 << 32) + 4);
+    /// assert!(!treemap.contains((1 << 32) - 3));
+    /// assert!(treemap.contains((1 << 32) - 2));
+    /// assert!(treemap.contains(2 << 32));
+    /// assert!(treemap.contains((3 << 32) + 1));
+    /// assert!(!treemap.contains((3 << 32) + 2));
+    ///
+    /// let mut treemap2 = Treemap::create();
+    /// treemap2.add_range(u64::MAX - 1..=u64::MAX);
+    /// assert_eq!(treemap2.to_vec(), [u64::MAX - 1, u64::MAX]);
+    ///
+    /// // Empty ranges do nothing
+    /// treemap2.add_range(5..5);
+    /// treemap2.add_range(5..1);
+    /// assert_eq!(treemap2.cardinality(), 2);
+    /// ```
+    pub fn add_range<R: RangeBounds<u64>>(&mut self, range: R) {
+        let (start, end) = range_to_inclusive(range);
+        if start > end {
+            return;
+        }
+        let (start_hi, start_lo) = util::split(start);
+        let (end_hi, end_lo) = util::split(end);
+
+        for hi in start_hi..=end_hi {
+            let lo_start = if hi == start_hi { start_lo } else { 0 };
+            let lo_end = if hi == end_hi { end_lo } else { u32::MAX };
+            self.map
+                .entry(hi)
+                .or_insert_with(Bitmap::create)
+                .add_range(lo_start..=lo_end);
+        }
+    }
+
+    /// Remove all values in range
+    ///
+    /// Bounds are interpreted the same way as in [`Treemap::add_range`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let mut treemap = Treemap::create();
+    /// treemap.add_range(0..(4 << 32));
+    /// treemap.add(u64::MAX);
+    ///
+    /// treemap.remove_range(10..=(3 << 32));
+    ///
+    /// assert_eq!(treemap.cardinality(), 10 + (1 << 32) - 1 + 1);
+    /// assert!(treemap.contains(9));
+    /// assert!(!treemap.contains(10));
+    /// assert!(!treemap.contains(3 << 32));
+    /// assert!(treemap.contains((3 << 32) + 1));
+    ///
+    /// treemap.remove_range(..);
+    /// assert!(treemap.is_empty());
+    /// ```
+    pub fn remove_range<R: RangeBounds<u64>>(&mut self, range: R) {
+        let (start, end) = range_to_inclusive(range);
+        if start > end {
+            return;
+        }
+        let (start_hi, start_lo) = util::split(start);
+        let (end_hi, end_lo) = util::split(end);
+        let mut keys_to_remove: Vec<u32> = Vec::new();
+
+        for (&hi, bitmap) in self.map.range_mut(start_hi..=end_hi) {
+            let lo_start = if hi == start_hi { start_lo } else { 0 };
+            let lo_end = if hi == end_hi { end_lo } else { u32::MAX };
+            bitmap.remove_range(lo_start..=lo_end);
+            if bitmap.is_empty() {
+                keys_to_remove.push(hi);
+            }
+        }
+
+        for key in keys_to_remove {
+            self.map.remove(&key);
+        }
+    }
+
+    /// Negates the values in range, returning the result as a new Treemap
+    ///
+    /// Bounds are interpreted the same way as in [`Treemap::add_range`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let treemap1 = Treemap::of(&[1, (1 << 32) + 1]);
+    /// let treemap2 = treemap1.flip((1 << 32) - 2..(1 << 32) + 2);
+    ///
+    /// assert_eq!(
+    ///     treemap2.to_vec(),
+    ///     [1, (1 << 32) - 2, (1 << 32) - 1, 1 << 32]
+    /// );
+    /// assert_eq!(treemap2.flip((1 << 32) - 2..(1 << 32) + 2), treemap1);
+    /// ```
+    pub fn flip<R: RangeBounds<u64>>(&self, range: R) -> Self {
+        let mut result = self.clone();
+        result.flip_inplace(range);
+        result
+    }
+
+    /// Negates the values in range, storing the result in the current Treemap
+    ///
+    /// Bounds are interpreted the same way as in [`Treemap::add_range`]. Every high
+    /// key in the range is visited, so a high key which had no values and lies
+    /// entirely within the range receives a full inner bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let mut treemap = Treemap::of(&[5, 3 << 32]);
+    /// treemap.flip_inplace(0..(2 << 32));
+    ///
+    /// assert_eq!(treemap.cardinality(), 2 << 32);
+    /// assert!(!treemap.contains(5));
+    /// assert!(treemap.contains(6));
+    /// assert!(treemap.contains((1 << 32) + 5));
+    /// assert!(treemap.contains(3 << 32));
+    ///
+    /// treemap.flip_inplace(..(2 << 32));
+    /// assert_eq!(treemap.to_vec(), [5, 3 << 32]);
+    /// ```
+    pub fn flip_inplace<R: RangeBounds<u64>>(&mut self, range: R) {
+        let (start, end) = range_to_inclusive(range);
+        if start > end {
+            return;
+        }
+        let (start_hi, start_lo) = util::split(start);
+        let (end_hi, end_lo) = util::split(end);
+
+        for hi in start_hi..=end_hi {
+            let lo_start = if hi == start_hi { start_lo } else { 0 };
+            let lo_end = if hi == end_hi { end_lo } else { u32::MAX };
+            match self.map.entry(hi) {
+                Entry::Occupied(mut entry) => {
+                    entry.get_mut().flip_inplace(lo_start..=lo_end);
+                    if entry.get().is_empty() {
+                        entry.remove();
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(Bitmap::from_range(lo_start..=lo_end));
+                }
+            }
+        }
+    }
+
+    /// Returns true if the value is present in the Treemap.
+    ///
+    /// # Examples
+    ///
     /// ```rust
     /// use croaring::Treemap;
     ///
     /// let mut treemap = Treemap::create();
+    /// treemap.add(1 << 40);
+    ///
+    /// assert!(treemap.contains(1 << 40));
+    /// assert!(!treemap.contains(1));
     /// ```
     pub fn contains(&self, value: u64) -> bool {
         let (hi, lo) = util::split(value);
@@ -141,12 +316,33 @@ impl Treemap {
     ///
     /// assert_eq!(treemap.cardinality(), 2);
     /// ```
+    ///
+    /// A treemap holding every `u64` value has 2^64 elements, one more than fits in
+    /// a `u64`, so the cardinality saturates at `u64::MAX` rather than overflowing.
+    /// Use [`Treemap::is_full`] to tell a full treemap apart from one missing a value.
     pub fn cardinality(&self) -> u64 {
-        self.map.values().map(Bitmap::cardinality).sum()
+        saturating_sum(self.map.values().map(Bitmap::cardinality))
+    }
+
+    /// Returns true if the Treemap contains every u64 value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let mut treemap = Treemap::create();
+    /// treemap.add_range(0..(3 << 32));
+    ///
+    /// assert!(!treemap.is_full());
+    /// assert_eq!(treemap.cardinality(), 3 << 32);
+    /// ```
+    pub fn is_full(&self) -> bool {
+        self.map.len() as u64 == 1 << 32 && self.map.values().all(Bitmap::is_full)
     }
 
     /// Returns the smallest value in the set.
-    /// Returns std::u64::MAX if the set is empty.
+    /// Returns None if the set is empty.
     ///
     /// # Examples
     ///
@@ -161,6 +357,12 @@ impl Treemap {
     ///
     /// assert_eq!(treemap.minimum(), Some(120));
     /// assert_eq!(empty_treemap.minimum(), None);
+    ///
+    /// treemap.add(u64::MAX);
+    /// treemap.remove(120);
+    /// treemap.remove(1000);
+    ///
+    /// assert_eq!(treemap.minimum(), Some(u64::MAX));
     /// ```
     pub fn minimum(&self) -> Option<u64> {
         self.map
@@ -171,7 +373,7 @@ impl Treemap {
     }
 
     /// Returns the greatest value in the set.
-    /// Returns 0 if the set is empty.
+    /// Returns None if the set is empty.
     ///
     /// # Examples
     ///
@@ -186,6 +388,11 @@ impl Treemap {
     ///
     /// assert_eq!(treemap.maximum(), Some(1000));
     /// assert_eq!(empty_treemap.maximum(), None);
+    ///
+    /// treemap.add(3 << 32);
+    ///
+    /// assert_eq!(treemap.minimum(), Some(120));
+    /// assert_eq!(treemap.maximum(), Some(3 << 32));
     /// ```
     pub fn maximum(&self) -> Option<u64> {
         self.map
@@ -196,6 +403,71 @@ impl Treemap {
             .next()
     }
 
+    /// Returns the number of integers that are smaller or equal to `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let treemap = Treemap::of(&[5, 10, 1 << 32, (1 << 32) + 5, u64::MAX]);
+    ///
+    /// assert_eq!(treemap.rank(4), 0);
+    /// assert_eq!(treemap.rank(5), 1);
+    /// assert_eq!(treemap.rank(u64::from(u32::MAX)), 2);
+    /// assert_eq!(treemap.rank((1 << 32) + 1), 3);
+    /// assert_eq!(treemap.rank(u64::MAX), 5);
+    ///
+    /// assert_eq!(Treemap::create().rank(u64::MAX), 0);
+    /// ```
+    pub fn rank(&self, value: u64) -> u64 {
+        let (hi, lo) = util::split(value);
+        let below: u64 = self.map.range(..hi).map(|(_, bitmap)| bitmap.cardinality()).sum();
+
+        match self.map.get(&hi) {
+            Some(bitmap) => below + bitmap.rank(lo),
+            None => below,
+        }
+    }
+
+    /// Select returns the element having the designated rank, if it exists
+    /// If the cardinality of the treemap is strictly greater than rank,
+    /// then this function returns element of given rank wrapped in Some.
+    /// Otherwise, it returns None.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let treemap = Treemap::of(&[5, 10, 1 << 32, (1 << 32) + 5, u64::MAX]);
+    ///
+    /// assert_eq!(treemap.select(0), Some(5));
+    /// assert_eq!(treemap.select(2), Some(1 << 32));
+    /// assert_eq!(treemap.select(3), Some((1 << 32) + 5));
+    /// assert_eq!(treemap.select(4), Some(u64::MAX));
+    /// assert_eq!(treemap.select(5), None);
+    ///
+    /// // select is the inverse of rank
+    /// for rank in 0..5 {
+    ///     let value = treemap.select(rank).unwrap();
+    ///     assert_eq!(treemap.rank(value) - 1, rank);
+    /// }
+    /// ```
+    pub fn select(&self, rank: u64) -> Option<u64> {
+        let mut rank = rank;
+
+        for (&key, bitmap) in &self.map {
+            let cardinality = bitmap.cardinality();
+            if rank < cardinality {
+                return bitmap.select(rank as u32).map(|lo| util::join(key, lo));
+            }
+            rank -= cardinality;
+        }
+
+        None
+    }
+
     /// And computes the intersection between two treemaps and returns the
     /// result as a new treemap
     ///
@@ -221,10 +493,12 @@ impl Treemap {
         let mut treemap = Treemap::create();
 
         for (key, bitmap) in &self.map {
-            other
-                .map
-                .get(key)
-                .map(|other_bitmap| treemap.map.insert(*key, bitmap.and(other_bitmap)));
+            if let Some(other_bitmap) = other.map.get(key) {
+                let result = bitmap.and(other_bitmap);
+                if !result.is_empty() {
+                    treemap.map.insert(*key, result);
+                }
+            }
         }
 
         treemap
@@ -318,7 +592,9 @@ impl Treemap {
         for (key, other_bitmap) in &other.map {
             match treemap.map.entry(*key) {
                 Entry::Vacant(current_map) => {
-                    current_map.insert(other_bitmap.clone());
+                    if !other_bitmap.is_empty() {
+                        current_map.insert(other_bitmap.clone());
+                    }
                 }
                 Entry::Occupied(mut bitmap) => {
                     bitmap.get_mut().or_inplace(other_bitmap);
@@ -366,7 +642,9 @@ impl Treemap {
         for (key, other_bitmap) in &other.map {
             match self.map.entry(*key) {
                 Entry::Vacant(current_map) => {
-                    current_map.insert(other_bitmap.clone());
+                    if !other_bitmap.is_empty() {
+                        current_map.insert(other_bitmap.clone());
+                    }
                 }
                 Entry::Occupied(mut current_map) => {
                     current_map.get_mut().or_inplace(other_bitmap);
@@ -401,18 +679,7 @@ impl Treemap {
     /// ```
     pub fn xor(&self, other: &Self) -> Self {
         let mut treemap = self.clone();
-
-        for (key, other_bitmap) in &other.map {
-            match treemap.map.entry(*key) {
-                Entry::Vacant(current_map) => {
-                    current_map.insert(other_bitmap.clone());
-                }
-                Entry::Occupied(mut bitmap) => {
-                    bitmap.get_mut().xor_inplace(other_bitmap);
-                }
-            };
-        }
-
+        treemap.xor_inplace(other);
         treemap
     }
 
@@ -450,7 +717,9 @@ impl Treemap {
         for (key, other_bitmap) in &other.map {
             match self.map.entry(*key) {
                 Entry::Vacant(bitmap) => {
-                    bitmap.insert(other_bitmap.clone());
+                    if !other_bitmap.is_empty() {
+                        bitmap.insert(other_bitmap.clone());
+                    }
                 }
                 Entry::Occupied(mut bitmap) => {
                     bitmap.get_mut().xor_inplace(other_bitmap);
@@ -495,8 +764,12 @@ impl Treemap {
         let mut treemap = Treemap::create();
 
         for (key, bitmap) in &self.map {
-            if let Some(other_bitmap) = other.map.get(key) {
-                treemap.map.insert(*key, bitmap.andnot(other_bitmap));
+            let result = match other.map.get(key) {
+                Some(other_bitmap) => bitmap.andnot(other_bitmap),
+                None => bitmap.clone(),
+            };
+            if !result.is_empty() {
+                treemap.map.insert(*key, result);
             }
         }
 
@@ -540,11 +813,179 @@ impl Treemap {
     /// assert!(treemap3.contains(15));
     /// ```
     pub fn andnot_inplace(&mut self, other: &Self) {
+        let mut keys_to_remove: Vec<u32> = Vec::new();
+
         for (key, bitmap) in &mut self.map {
             if let Some(other_bitmap) = other.map.get(key) {
                 bitmap.andnot_inplace(other_bitmap);
+                if bitmap.is_empty() {
+                    keys_to_remove.push(*key);
+                }
             }
         }
+
+        for key in keys_to_remove {
+            self.map.remove(&key);
+        }
+    }
+
+    /// Return the size of the intersection between Self and &other
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let treemap1 = Treemap::of(&[1, 2, u64::MAX]);
+    /// let treemap2 = Treemap::of(&[2, 3, 1 << 40]);
+    ///
+    /// assert_eq!(treemap1.and_cardinality(&treemap2), 1);
+    /// ```
+    pub fn and_cardinality(&self, other: &Self) -> u64 {
+        saturating_sum(self.map.iter().filter_map(|(key, bitmap)| {
+            other
+                .map
+                .get(key)
+                .map(|other_bitmap| bitmap.and_cardinality(other_bitmap))
+        }))
+    }
+
+    /// Return the size of the union between Self and &other
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let treemap1 = Treemap::of(&[1, 2, u64::MAX]);
+    /// let treemap2 = Treemap::of(&[2, 3, 1 << 40]);
+    ///
+    /// assert_eq!(treemap1.or_cardinality(&treemap2), 5);
+    /// ```
+    pub fn or_cardinality(&self, other: &Self) -> u64 {
+        self.combined_cardinality(other, Bitmap::or_cardinality)
+    }
+
+    /// Return the size of the difference between Self and &other
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let treemap1 = Treemap::of(&[1, 2, u64::MAX]);
+    /// let treemap2 = Treemap::of(&[2, 3, 1 << 40]);
+    ///
+    /// assert_eq!(treemap1.andnot_cardinality(&treemap2), 2);
+    /// ```
+    pub fn andnot_cardinality(&self, other: &Self) -> u64 {
+        saturating_sum(self.map.iter().map(|(key, bitmap)| match other.map.get(key) {
+            Some(other_bitmap) => bitmap.andnot_cardinality(other_bitmap),
+            None => bitmap.cardinality(),
+        }))
+    }
+
+    /// Return the size of the symmetric difference between Self and &other
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let treemap1 = Treemap::of(&[1, 2, u64::MAX]);
+    /// let treemap2 = Treemap::of(&[2, 3, 1 << 40]);
+    ///
+    /// assert_eq!(treemap1.xor_cardinality(&treemap2), 4);
+    /// ```
+    pub fn xor_cardinality(&self, other: &Self) -> u64 {
+        self.combined_cardinality(other, Bitmap::xor_cardinality)
+    }
+
+    /// Sums `both` over the high keys present in both treemaps, and the
+    /// cardinalities of the bitmaps whose high key is only in one of them
+    fn combined_cardinality(&self, other: &Self, both: fn(&Bitmap, &Bitmap) -> u64) -> u64 {
+        let in_self = self.map.iter().map(|(key, bitmap)| match other.map.get(key) {
+            Some(other_bitmap) => both(bitmap, other_bitmap),
+            None => bitmap.cardinality(),
+        });
+        let only_in_other = other
+            .map
+            .iter()
+            .filter(|(key, _)| !self.map.contains_key(key))
+            .map(|(_, bitmap)| bitmap.cardinality());
+        saturating_sum(in_self.chain(only_in_other))
+    }
+
+    /// Computes the Jaccard index between two treemaps
+    ///
+    /// The Jaccard index is the size of the intersection divided by the size
+    /// of the union. As with [`Bitmap::jaccard_index`], if both treemaps are
+    /// empty, the result is `NaN`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let treemap1: Treemap = (1..5).collect();
+    /// let treemap2: Treemap = (5..9).collect();
+    /// let treemap3: Treemap = (3..9).chain([u64::MAX, u64::MAX - 1]).collect();
+    ///
+    /// assert_eq!(treemap1.jaccard_index(&treemap2), 0.0);
+    /// assert_eq!(treemap1.jaccard_index(&treemap3), 0.2);
+    /// assert_eq!(treemap1.jaccard_index(&treemap1), 1.0);
+    ///
+    /// let empty_treemap = Treemap::create();
+    /// assert_eq!(treemap1.jaccard_index(&empty_treemap), 0.0);
+    /// assert!(empty_treemap.jaccard_index(&empty_treemap).is_nan());
+    /// ```
+    pub fn jaccard_index(&self, other: &Self) -> f64 {
+        self.and_cardinality(other) as f64 / self.or_cardinality(other) as f64
+    }
+
+    /// Return true if all the elements of Self are in &other
+    ///
+    /// High keys present only in &other are ignored, and high keys present
+    /// only in Self make it a subset only if their bitmaps are empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let treemap1 = Treemap::of(&[1, 1 << 40]);
+    /// let treemap2 = Treemap::of(&[1, 2, 1 << 40, u64::MAX]);
+    ///
+    /// assert!(treemap1.is_subset(&treemap2));
+    /// assert!(!treemap2.is_subset(&treemap1));
+    /// assert!(Treemap::create().is_subset(&treemap1));
+    /// ```
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.map.iter().all(|(key, bitmap)| match other.map.get(key) {
+            Some(other_bitmap) => bitmap.is_subset(other_bitmap),
+            None => bitmap.is_empty(),
+        })
+    }
+
+    /// Return true if Self and &other have at least one element in common
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let treemap1 = Treemap::of(&[1, 1 << 40]);
+    /// let treemap2 = Treemap::of(&[2, 1 << 40]);
+    /// let treemap3 = Treemap::of(&[2, u64::MAX]);
+    ///
+    /// assert!(treemap1.intersect(&treemap2));
+    /// assert!(!treemap1.intersect(&treemap3));
+    /// ```
+    pub fn intersect(&self, other: &Self) -> bool {
+        self.map.iter().any(|(key, bitmap)| match other.map.get(key) {
+            Some(other_bitmap) => bitmap.intersect(other_bitmap),
+            None => false,
+        })
     }
 
     /// Returns a vector containing all of the integers stored in the Treemap
@@ -615,24 +1056,87 @@ impl Treemap {
         treemap
     }
 
-    /// Compresses treemap's bitmaps. Returns true if any of the bitmaps
-    /// were modified.
+    /// Adds all values of `bitmap` to the treemap, with `high` as their high 32 bits
+    ///
+    /// The bitmap is moved into the treemap rather than copied value by value. If the
+    /// treemap already has values under `high`, the two are unioned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, Treemap};
+    ///
+    /// let mut treemap = Treemap::of(&[1, (7 << 32) + 2]);
+    ///
+    /// treemap.add_bitmap_at(3, Bitmap::of(&[10, 20]));
+    /// treemap.add_bitmap_at(7, Bitmap::of(&[1, 2]));
+    /// treemap.add_bitmap_at(9, Bitmap::create());
+    ///
+    /// assert_eq!(
+    ///     treemap.to_vec(),
+    ///     [1, (3 << 32) + 10, (3 << 32) + 20, (7 << 32) + 1, (7 << 32) + 2]
+    /// );
+    /// ```
+    pub fn add_bitmap_at(&mut self, high: u32, bitmap: Bitmap) {
+        if bitmap.is_empty() {
+            return;
+        }
+
+        match self.map.entry(high) {
+            Entry::Vacant(entry) => {
+                entry.insert(bitmap);
+            }
+            Entry::Occupied(mut entry) => entry.get_mut().or_inplace_owned(bitmap),
+        }
+    }
+
+    /// Converts the treemap into a 32 bit bitmap, if all of its values fit in 32 bits
+    ///
+    /// Returns None if any value is greater than `u32::MAX`. The bitmap of values
+    /// under high key 0 is returned as is, without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, Treemap};
+    ///
+    /// let treemap = Treemap::of(&[1, 2, u64::from(u32::MAX)]);
+    /// assert_eq!(treemap.into_bitmap(), Some(Bitmap::of(&[1, 2, u32::MAX])));
+    ///
+    /// assert_eq!(Treemap::create().into_bitmap(), Some(Bitmap::create()));
+    /// assert_eq!(Treemap::of(&[1, 1 << 32]).into_bitmap(), None);
+    /// ```
+    pub fn into_bitmap(mut self) -> Option<Bitmap> {
+        let bitmap = self.map.remove(&0).unwrap_or_else(Bitmap::create);
+        if self.map.values().all(Bitmap::is_empty) {
+            Some(bitmap)
+        } else {
+            None
+        }
+    }
+
+    /// Compresses each of the treemap's bitmaps. Returns true if any of the
+    /// bitmaps has at least one run container afterwards.
     ///
     /// # Examples
     ///
     /// ```
     /// use croaring::Treemap;
     ///
-    /// let mut treemap: Treemap = (100..1000).collect();
+    /// let mut treemap: Treemap = (100..1000).chain((1 << 32)..(1 << 32) + 1000).collect();
+    ///
+    /// assert_eq!(treemap.cardinality(), 1900);
+    /// assert_eq!(treemap.statistics().n_run_containers, 0);
     ///
-    /// assert_eq!(treemap.cardinality(), 900);
     /// assert!(treemap.run_optimize());
+    /// assert_eq!(treemap.statistics().n_run_containers, 2);
     /// ```
     pub fn run_optimize(&mut self) -> bool {
-        self.map.iter_mut().fold(
-            false,
-            |result, (_, bitmap)| result || bitmap.run_optimize()
-        )
+        let mut changed = false;
+        for bitmap in self.map.values_mut() {
+            changed |= bitmap.run_optimize();
+        }
+        changed
     }
 
     /// Removes run-length encoding from treemap's bitmaps. Returns true if
@@ -650,9 +1154,107 @@ impl Treemap {
     /// assert!(treemap.remove_run_compression());
     /// ```
     pub fn remove_run_compression(&mut self) -> bool {
-        self.map.iter_mut().fold(
-            false,
-            |result, (_, bitmap)| result || bitmap.remove_run_compression()
-        )
+        let mut changed = false;
+        for bitmap in self.map.values_mut() {
+            changed |= bitmap.remove_run_compression();
+        }
+        changed
     }
+
+    /// Returns statistics about the composition of the treemap, summed over
+    /// all of its bitmaps.
+    ///
+    /// Counts saturate rather than overflow. `min_value` and `max_value` are the
+    /// low 32 bits of [`Treemap::minimum`] and [`Treemap::maximum`], and
+    /// `sum_value` is the sum of the low 32 bits of all values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let mut treemap: Treemap = (1..100).chain((1 << 32)..(1 << 32) + 10).collect();
+    /// let statistics = treemap.statistics();
+    ///
+    /// assert_eq!(statistics.n_containers, 2);
+    /// assert_eq!(statistics.n_array_containers, 2);
+    /// assert_eq!(statistics.n_values_array_containers, 109);
+    /// assert_eq!(statistics.min_value, 1);
+    /// assert_eq!(statistics.max_value, 9);
+    /// assert_eq!(statistics.cardinality, treemap.cardinality());
+    /// ```
+    pub fn statistics(&self) -> Statistics {
+        let mut result = Statistics::default();
+
+        for bitmap in self.map.values().filter(|bitmap| !bitmap.is_empty()) {
+            let stats = bitmap.statistics();
+            result.n_containers = result.n_containers.saturating_add(stats.n_containers);
+            result.n_array_containers = result.n_array_containers.saturating_add(stats.n_array_containers);
+            result.n_run_containers = result.n_run_containers.saturating_add(stats.n_run_containers);
+            result.n_bitset_containers = result.n_bitset_containers.saturating_add(stats.n_bitset_containers);
+            result.n_values_array_containers = result.n_values_array_containers.saturating_add(stats.n_values_array_containers);
+            result.n_values_run_containers = result.n_values_run_containers.saturating_add(stats.n_values_run_containers);
+            result.n_values_bitset_containers = result.n_values_bitset_containers.saturating_add(stats.n_values_bitset_containers);
+            result.n_bytes_array_containers = result.n_bytes_array_containers.saturating_add(stats.n_bytes_array_containers);
+            result.n_bytes_run_containers = result.n_bytes_run_containers.saturating_add(stats.n_bytes_run_containers);
+            result.n_bytes_bitset_containers = result.n_bytes_bitset_containers.saturating_add(stats.n_bytes_bitset_containers);
+            result.sum_value = result.sum_value.saturating_add(stats.sum_value);
+            result.cardinality = result.cardinality.saturating_add(stats.cardinality);
+        }
+
+        if let (Some(minimum), Some(maximum)) = (self.minimum(), self.maximum()) {
+            result.min_value = util::split(minimum).1;
+            result.max_value = util::split(maximum).1;
+        }
+
+        result
+    }
+}
+
+impl From<Bitmap> for Treemap {
+    /// Creates a treemap holding the values of a 32 bit bitmap
+    ///
+    /// The bitmap is moved into the treemap as its high key 0, without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::{Bitmap, Treemap};
+    ///
+    /// let bitmap = Bitmap::of(&[1, 2, u32::MAX]);
+    /// let treemap = Treemap::from(bitmap.clone());
+    ///
+    /// assert_eq!(treemap.to_vec(), [1, 2, u64::from(u32::MAX)]);
+    /// assert_eq!(treemap.into_bitmap(), Some(bitmap));
+    /// ```
+    fn from(bitmap: Bitmap) -> Self {
+        let mut treemap = Treemap::create();
+        treemap.add_bitmap_at(0, bitmap);
+        treemap
+    }
+}
+
+/// Sums cardinalities, saturating at `u64::MAX`: a treemap can hold 2^64 values
+fn saturating_sum<I: Iterator<Item = u64>>(cardinalities: I) -> u64 {
+    cardinalities.fold(0, u64::saturating_add)
+}
+
+fn range_to_inclusive<R: RangeBounds<u64>>(range: R) -> (u64, u64) {
+    let start = match range.start_bound() {
+        Bound::Included(&i) => i,
+        Bound::Excluded(&i) => match i.checked_add(1) {
+            Some(i) => i,
+            None => return (1, 0),
+        },
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&i) => i,
+        Bound::Excluded(&i) => match i.checked_sub(1) {
+            Some(i) => i,
+            None => return (1, 0),
+        },
+        Bound::Unbounded => u64::MAX,
+    };
+    (start, end)
 }
@@ -1,14 +1,15 @@
-use std::collections::btree_map;
-use std::iter::{self, FromIterator};
+use alloc::collections::btree_map;
+use core::iter::FromIterator;
 use super::util;
 use super::{BitmapIterator, Treemap, Bitmap};
+use crate::BitmapIntoIterator;
 
-struct To64Iter<'a> {
+struct To64Iter<I> {
     key: u32,
-    iterator: BitmapIterator<'a>,
+    iterator: I,
 }
 
-impl<'a> Iterator for To64Iter<'a> {
+impl<I: Iterator<Item = u32>> Iterator for To64Iter<I> {
     type Item = u64;
 
     fn next(&mut self) -> Option<u64> {
@@ -16,28 +17,100 @@ impl<'a> Iterator for To64Iter<'a> {
     }
 }
 
-fn to64iter<'a>(t: (&'a u32, &'a Bitmap)) -> To64Iter<'a> {
+impl<I: DoubleEndedIterator<Item = u32>> DoubleEndedIterator for To64Iter<I> {
+    fn next_back(&mut self) -> Option<u64> {
+        self.iterator.next_back().map(|n| util::join(self.key, n))
+    }
+}
+
+fn to64iter<'a>(t: (&'a u32, &'a Bitmap)) -> To64Iter<BitmapIterator<'a>> {
     To64Iter {
         key: *t.0,
         iterator: t.1.iter(),
     }
 }
 
-type InnerIter<'a> = iter::FlatMap<btree_map::Iter<'a, u32, Bitmap>,
-                                   To64Iter<'a>,
-                                   fn((&'a u32, &'a Bitmap)) -> To64Iter<'a>>;
+fn to64intoiter(t: (u32, Bitmap)) -> To64Iter<BitmapIntoIterator> {
+    To64Iter {
+        key: t.0,
+        iterator: t.1.into_iter(),
+    }
+}
 
+/// Iterator over the values in a treemap, see [`Treemap::iter`]
+///
+/// Walks the inner bitmaps in order of their high 32 bits, from the front and/or the back.
 pub struct TreemapIterator<'a> {
-    iter: InnerIter<'a>,
+    iter: btree_map::Iter<'a, u32, Bitmap>,
+    front: Option<To64Iter<BitmapIterator<'a>>>,
+    back: Option<To64Iter<BitmapIterator<'a>>>,
 }
 
 impl<'a> TreemapIterator<'a> {
     fn new(treemap: &'a Treemap) -> Self {
-        let iter = treemap.map
-                       .iter()
-                       .flat_map(to64iter as _);
+        TreemapIterator {
+            iter: treemap.map.iter(),
+            front: None,
+            back: None,
+        }
+    }
+}
 
-        TreemapIterator { iter }
+impl<'a> TreemapIterator<'a> {
+    /// Retrieve the next `dst.len()` values from the treemap, and write them to `dst`.
+    /// Values are read in ascending order, continuing from one inner bitmap to the next.
+    ///
+    /// Returns the number of values written to `dst`, which will be less than `dst.len()`
+    /// only if the iterator is exhausted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let treemap: Treemap = [1, 2, 1 << 32, (1 << 32) + 1, u64::MAX].iter().copied().collect();
+    /// let mut iter = treemap.iter();
+    /// let mut buf = [0; 3];
+    ///
+    /// assert_eq!(iter.next_many(&mut buf), 3);
+    /// assert_eq!(buf, [1, 2, 1 << 32]);
+    ///
+    /// assert_eq!(iter.next_back(), Some(u64::MAX));
+    /// assert_eq!(iter.next_many(&mut buf), 1);
+    /// assert_eq!(buf[0], (1 << 32) + 1);
+    ///
+    /// assert_eq!(iter.next_many(&mut buf), 0);
+    /// ```
+    pub fn next_many(&mut self, dst: &mut [u64]) -> usize {
+        let mut buf = [0u32; 256];
+        let mut count = 0;
+
+        while count < dst.len() {
+            if self.front.is_none() {
+                match self.iter.next() {
+                    Some(entry) => self.front = Some(to64iter(entry)),
+                    None => match self.back.take() {
+                        Some(back) => self.front = Some(back),
+                        None => break,
+                    },
+                }
+            }
+
+            let front = self.front.as_mut().unwrap();
+            let wanted = (dst.len() - count).min(buf.len());
+            let read = front.iterator.next_many(&mut buf[..wanted]);
+            if read == 0 {
+                self.front = None;
+                continue;
+            }
+
+            for (value, &low) in dst[count..count + read].iter_mut().zip(&buf[..read]) {
+                *value = util::join(front.key, low);
+            }
+            count += read;
+        }
+
+        count
     }
 }
 
@@ -45,7 +118,29 @@ impl<'a> Iterator for TreemapIterator<'a> {
     type Item = u64;
 
     fn next(&mut self) -> Option<u64> {
-        self.iter.next()
+        loop {
+            if let Some(value) = self.front.as_mut().and_then(Iterator::next) {
+                return Some(value);
+            }
+            match self.iter.next() {
+                Some(entry) => self.front = Some(to64iter(entry)),
+                None => return self.back.as_mut().and_then(Iterator::next),
+            }
+        }
+    }
+}
+
+impl<'a> DoubleEndedIterator for TreemapIterator<'a> {
+    fn next_back(&mut self) -> Option<u64> {
+        loop {
+            if let Some(value) = self.back.as_mut().and_then(DoubleEndedIterator::next_back) {
+                return Some(value);
+            }
+            match self.iter.next_back() {
+                Some(entry) => self.back = Some(to64iter(entry)),
+                None => return self.front.as_mut().and_then(DoubleEndedIterator::next_back),
+            }
+        }
     }
 }
 
@@ -73,11 +168,102 @@ impl Treemap {
     /// assert_eq!(iterator.next(), Some(u64::MAX));
     /// assert_eq!(iterator.next(), None);
     /// ```
+    ///
+    /// Values from different inner bitmaps come out in sorted order from either end:
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let values = [1, 5, 1 << 32, (1 << 32) + 7, 3 << 40, u64::MAX];
+    /// let treemap: Treemap = values.iter().rev().copied().collect();
+    ///
+    /// assert_eq!(treemap.iter().collect::<Vec<u64>>(), values);
+    ///
+    /// let mut reversed = values.to_vec();
+    /// reversed.reverse();
+    /// assert_eq!(treemap.iter().rev().collect::<Vec<u64>>(), reversed);
+    ///
+    /// let mut iterator = treemap.iter();
+    /// assert_eq!(iterator.next(), Some(1));
+    /// assert_eq!(iterator.next_back(), Some(u64::MAX));
+    /// assert_eq!(iterator.next_back(), Some(3 << 40));
+    /// assert_eq!(iterator.next(), Some(5));
+    /// ```
     pub fn iter(&self) -> TreemapIterator {
         TreemapIterator::new(self)
     }
 }
 
+impl<'a> IntoIterator for &'a Treemap {
+    type Item = u64;
+    type IntoIter = TreemapIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Owning iterator over the values in a treemap, see [`Treemap::into_iter`]
+pub struct TreemapIntoIterator {
+    iter: btree_map::IntoIter<u32, Bitmap>,
+    front: Option<To64Iter<BitmapIntoIterator>>,
+    back: Option<To64Iter<BitmapIntoIterator>>,
+}
+
+impl Iterator for TreemapIntoIterator {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            if let Some(value) = self.front.as_mut().and_then(Iterator::next) {
+                return Some(value);
+            }
+            match self.iter.next() {
+                Some(entry) => self.front = Some(to64intoiter(entry)),
+                None => return self.back.as_mut().and_then(Iterator::next),
+            }
+        }
+    }
+}
+
+impl DoubleEndedIterator for TreemapIntoIterator {
+    fn next_back(&mut self) -> Option<u64> {
+        loop {
+            if let Some(value) = self.back.as_mut().and_then(DoubleEndedIterator::next_back) {
+                return Some(value);
+            }
+            match self.iter.next_back() {
+                Some(entry) => self.back = Some(to64intoiter(entry)),
+                None => return self.front.as_mut().and_then(DoubleEndedIterator::next_back),
+            }
+        }
+    }
+}
+
+impl IntoIterator for Treemap {
+    type Item = u64;
+    type IntoIter = TreemapIntoIterator;
+
+    /// Consumes the treemap, returning an iterator over its values in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let treemap: Treemap = [u64::MAX, 3, 1 << 32].iter().copied().collect();
+    ///
+    /// assert_eq!(treemap.into_iter().collect::<Vec<u64>>(), [3, 1 << 32, u64::MAX]);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        TreemapIntoIterator {
+            iter: self.map.into_iter(),
+            front: None,
+            back: None,
+        }
+    }
+}
+
 impl FromIterator<u64> for Treemap {
     /// Convenience method for creating treemap from an iterator.
     ///
@@ -104,6 +290,18 @@ impl FromIterator<u64> for Treemap {
 }
 
 impl Extend<u64> for Treemap {
+    /// Adds each value from the iterator to the treemap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let mut treemap = Treemap::of(&[1]);
+    /// treemap.extend(vec![u64::from(u32::MAX), 1 << 40]);
+    ///
+    /// assert_eq!(treemap.iter().collect::<Vec<u64>>(), [1, u64::from(u32::MAX), 1 << 40]);
+    /// ```
     fn extend<T: IntoIterator<Item=u64>>(&mut self, iter: T) {
         for item in iter {
             self.add(item);
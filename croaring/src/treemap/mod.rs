@@ -4,6 +4,9 @@
 //! Java version can be found at https://github.com/RoaringBitmap/RoaringBitmap/blob/master/roaringbitmap/src/main/java/org/roaringbitmap/longlong/Roaring64NavigableMap.java
 //! C++ version - https://github.com/RoaringBitmap/CRoaring/blob/master/cpp/roaring64map.hh
 //!
+//! The version of CRoaring bundled by `croaring-sys` does not include the native
+//! `roaring64_bitmap_t`, so Treemap is the only 64-bit bitmap type available.
+//!
 //! # Example
 //!
 //! ```rust
@@ -21,10 +24,11 @@
 //! ```
 use crate::Bitmap;
 use crate::BitmapIterator;
-use std::collections::BTreeMap;
+use alloc::collections::BTreeMap;
 
 mod imp;
 mod iter;
+#[cfg(feature = "std")]
 mod serialization;
 mod ops;
 mod util;
@@ -34,4 +38,6 @@ pub struct Treemap {
     pub map: BTreeMap<u32, Bitmap>,
 }
 
-pub use crate::treemap::serialization::{JvmSerializer, NativeSerializer};
+pub use crate::treemap::iter::{TreemapIntoIterator, TreemapIterator};
+#[cfg(feature = "std")]
+pub use crate::treemap::serialization::{JvmSerializer, NativeSerializer, PortableSerializer};
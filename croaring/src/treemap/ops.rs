@@ -1,5 +1,5 @@
-use std::fmt;
-use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
+use core::fmt;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
 
 use super::Treemap;
 
@@ -20,6 +20,15 @@ impl fmt::Debug for Treemap {
 }
 
 impl Default for Treemap {
+    /// Creates an empty treemap, the same as [`Treemap::create`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// assert!(Treemap::default().is_empty());
+    /// ```
     fn default() -> Self {
         Self::create()
     }
@@ -149,6 +158,29 @@ impl BitAndAssign for Treemap {
     }
 }
 
+impl BitAndAssign<&Treemap> for Treemap {
+    /// Syntactic sugar for `.and_inplace`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let mut treemap1 = Treemap::of(&[15, 1 << 32, 2 << 32]);
+    /// let treemap2 = Treemap::of(&[25, 1 << 32]);
+    ///
+    /// treemap1 &= &treemap2;
+    ///
+    /// assert_eq!(treemap1.cardinality(), 1);
+    /// assert!(treemap1.contains(1 << 32));
+    /// assert!(!treemap1.contains(15));
+    /// ```
+    #[inline]
+    fn bitand_assign(&mut self, other: &Treemap) {
+        self.and_inplace(other);
+    }
+}
+
 impl BitOr for Treemap {
     type Output = Treemap;
 
@@ -259,6 +291,30 @@ impl BitOrAssign for Treemap {
     }
 }
 
+impl BitOrAssign<&Treemap> for Treemap {
+    /// Syntatic sugar for `.or_inplace`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let mut treemap1 = Treemap::of(&[15]);
+    /// let treemap2 = Treemap::of(&[25, 1 << 32]);
+    ///
+    /// treemap1 |= &treemap2;
+    ///
+    /// assert_eq!(treemap1.cardinality(), 3);
+    /// assert!(treemap1.contains(15));
+    /// assert!(treemap1.contains(25));
+    /// assert!(treemap1.contains(1 << 32));
+    /// ```
+    #[inline]
+    fn bitor_assign(&mut self, other: &Treemap) {
+        self.or_inplace(other)
+    }
+}
+
 impl BitXor for Treemap {
     type Output = Treemap;
 
@@ -385,6 +441,30 @@ impl BitXorAssign for Treemap {
     }
 }
 
+impl BitXorAssign<&Treemap> for Treemap {
+    /// Syntatic sugar for `.xor_inplace`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let mut treemap1 = Treemap::of(&[15, 1 << 32]);
+    /// let treemap2 = Treemap::of(&[1 << 32, 2 << 32]);
+    ///
+    /// treemap1 ^= &treemap2;
+    ///
+    /// assert_eq!(treemap1.cardinality(), 2);
+    /// assert!(treemap1.contains(15));
+    /// assert!(!treemap1.contains(1 << 32));
+    /// assert!(treemap1.contains(2 << 32));
+    /// ```
+    #[inline]
+    fn bitxor_assign(&mut self, other: &Treemap) {
+        self.xor_inplace(other)
+    }
+}
+
 impl Sub for Treemap {
     type Output = Treemap;
 
@@ -472,7 +552,7 @@ impl<'a, 'b> Sub<&'a Treemap> for &'b Treemap {
     /// let mut treemap2 = Treemap::create();
     ///
     /// treemap2.add(25);
-    /// treemap1.add(u64::MAX);
+    /// treemap2.add(u64::MAX);
     ///
     /// let treemap3 = &treemap1 - &treemap2;
     ///
@@ -518,3 +598,27 @@ impl SubAssign for Treemap {
         self.andnot_inplace(&other)
     }
 }
+
+impl SubAssign<&Treemap> for Treemap {
+    /// Syntatic sugar for `.andnot_inplace`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    ///
+    /// let mut treemap1 = Treemap::of(&[15, 1 << 32, 2 << 32]);
+    /// let treemap2 = Treemap::of(&[25, 1 << 32]);
+    ///
+    /// treemap1 -= &treemap2;
+    ///
+    /// assert_eq!(treemap1.cardinality(), 2);
+    /// assert!(treemap1.contains(15));
+    /// assert!(!treemap1.contains(1 << 32));
+    /// assert!(treemap1.contains(2 << 32));
+    /// ```
+    #[inline]
+    fn sub_assign(&mut self, other: &Treemap) {
+        self.andnot_inplace(other)
+    }
+}
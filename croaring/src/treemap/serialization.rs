@@ -1,9 +1,9 @@
-use crate::Bitmap;
+use crate::{Bitmap, Portable};
 use crate::Treemap;
 
-use std::io::{Cursor, Result, Seek, SeekFrom};
+use std::io::{Cursor, Error, ErrorKind, Result, Seek, SeekFrom, Write};
 use std::mem::size_of;
-use byteorder::{NativeEndian, BigEndian, ReadBytesExt, WriteBytesExt};
+use byteorder::{NativeEndian, BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
 
 pub trait Serializer {}
 
@@ -28,7 +28,7 @@ impl NativeSerializer for Treemap {
 
         for (index, bitmap) in &self.map {
             buffer.write_u32::<NativeEndian>(*index)?;
-            let bitmap_buffer = bitmap.serialize();
+            let bitmap_buffer = bitmap.serialize::<Portable>();
             buffer.extend(bitmap_buffer);
         }
 
@@ -42,7 +42,7 @@ impl NativeSerializer for Treemap {
 
         for _ in 0..bitmap_count {
             let index = cursor.read_u32::<NativeEndian>()?;
-            let bitmap = Bitmap::deserialize(
+            let bitmap = Bitmap::deserialize::<Portable>(
                 &buffer[cursor.position() as usize..]
             );
             cursor.seek(
@@ -103,7 +103,7 @@ impl JvmSerializer for Treemap {
 
         for (index, bitmap) in &self.map {
             buffer.write_u32::<BigEndian>(*index)?;
-            let bitmap_buffer = bitmap.serialize();
+            let bitmap_buffer = bitmap.serialize::<Portable>();
             buffer.extend(bitmap_buffer);
         }
 
@@ -119,7 +119,7 @@ impl JvmSerializer for Treemap {
 
         for _ in 0..bitmap_count {
             let index = cursor.read_u32::<BigEndian>()?;
-            let bitmap = Bitmap::deserialize(
+            let bitmap = Bitmap::deserialize::<Portable>(
                 &buffer[cursor.position() as usize..]
             );
             cursor.seek(
@@ -158,3 +158,118 @@ impl JvmSerializer for Treemap {
         )
     }
 }
+
+/// croaring::Treemap serializer for the portable 64-bit format described in
+/// https://github.com/RoaringBitmap/RoaringFormatSpec#extension-for-64-bit-implementations
+///
+/// This is the format used by the Go, Java (`Roaring64Map`) and C++ implementations:
+/// a little-endian `u64` count of inner bitmaps, followed by each little-endian `u32`
+/// high key and its portable serialized bitmap.
+pub trait PortableSerializer: Serializer {
+    type Item;
+
+    fn serialize(&self) -> Result<Vec<u8>>;
+    fn serialize_into<W: Write>(&self, dst: W) -> Result<()>;
+    fn deserialize(buffer: &[u8]) -> Result<Self::Item>;
+    fn get_serialized_size_in_bytes(&self) -> usize;
+}
+
+impl PortableSerializer for Treemap {
+    type Item = Treemap;
+
+    /// Serializes the treemap into a new buffer
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    /// use croaring::treemap::PortableSerializer;
+    ///
+    /// let treemap = Treemap::of(&[1, 2, 1 << 32, std::u64::MAX]);
+    /// let buffer = treemap.serialize().unwrap();
+    ///
+    /// assert_eq!(buffer.len(), treemap.get_serialized_size_in_bytes());
+    /// assert_eq!(Treemap::deserialize(&buffer).unwrap(), treemap);
+    /// ```
+    fn serialize(&self) -> Result<Vec<u8>> {
+        let mut buffer = Vec::with_capacity(PortableSerializer::get_serialized_size_in_bytes(self));
+        PortableSerializer::serialize_into(self, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Serializes the treemap, writing the bytes to `dst`
+    fn serialize_into<W: Write>(&self, mut dst: W) -> Result<()> {
+        dst.write_u64::<LittleEndian>(self.map.len() as u64)?;
+
+        for (index, bitmap) in &self.map {
+            dst.write_u32::<LittleEndian>(*index)?;
+            dst.write_all(&bitmap.serialize::<Portable>())?;
+        }
+
+        Ok(())
+    }
+
+    /// Deserializes a treemap, validating the input
+    ///
+    /// Truncated or otherwise invalid input results in an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    /// use croaring::treemap::PortableSerializer;
+    ///
+    /// let buffer = Treemap::of(&[1, 1 << 40]).serialize().unwrap();
+    ///
+    /// assert!(Treemap::deserialize(&buffer[..buffer.len() - 1]).is_err());
+    /// assert!(Treemap::deserialize(&[1, 0, 0, 0, 0, 0, 0, 0]).is_err());
+    /// ```
+    fn deserialize(buffer: &[u8]) -> Result<Self::Item> {
+        let mut cursor = Cursor::new(buffer);
+        let mut treemap = Treemap::create();
+        let bitmap_count = cursor.read_u64::<LittleEndian>()?;
+
+        for _ in 0..bitmap_count {
+            let index = cursor.read_u32::<LittleEndian>()?;
+            let remaining = &buffer[cursor.position() as usize..];
+            let bitmap = Bitmap::try_deserialize::<Portable>(remaining).ok_or_else(|| {
+                Error::new(ErrorKind::InvalidData, "invalid serialized bitmap")
+            })?;
+            cursor.seek(
+                SeekFrom::Current(bitmap.get_serialized_size_in_bytes() as i64)
+            )?;
+            if treemap.map.insert(index, bitmap).is_some() {
+                return Err(Error::new(ErrorKind::InvalidData, "duplicate high key"));
+            }
+        }
+
+        Ok(treemap)
+    }
+
+    /// How many bytes are required to serialize this bitmap with
+    /// PortableSerializer
+    ///
+    /// This is exactly the length of the buffer returned by `serialize`, so it can
+    /// be used to preallocate space for the treemap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use croaring::Treemap;
+    /// use croaring::treemap::PortableSerializer;
+    ///
+    /// let treemap = Treemap::of(&[1, 2, 1 << 32, 5 << 32, std::u64::MAX]);
+    /// let size = treemap.get_serialized_size_in_bytes();
+    ///
+    /// let mut buffer = Vec::with_capacity(size);
+    /// treemap.serialize_into(&mut buffer).unwrap();
+    /// assert_eq!(buffer.len(), size);
+    /// assert_eq!(buffer.capacity(), size);
+    /// ```
+    fn get_serialized_size_in_bytes(&self) -> usize {
+        self.map.iter().fold(
+            size_of::<u64>() + self.map.len() * size_of::<u32>(),
+            |sum, (_, bitmap)| sum + bitmap.get_serialized_size_in_bytes()
+        )
+    }
+}
@@ -1,8 +1,10 @@
+use std::collections::{BTreeSet, VecDeque};
 use std::fs::File;
 use std::io::{Read, Result};
 use std::u32;
 
-use croaring::{Bitmap, Treemap};
+use croaring::bitmap::Serializer;
+use croaring::{Bitmap, BitmapError, DeserializeError, Frozen, FrozenBitmapView, FrozenViewError, Native, Portable, Treemap};
 use proptest::prelude::*;
 
 // borrowed and adapted from https://github.com/Nemo157/roaring-rs/blob/5089f180ca7e17db25f5c58023f4460d973e747f/tests/lib.rs#L7-L37
@@ -119,35 +121,1708 @@ fn test_treemap_deserialize_cpp() {
     }
 }
 
+#[test]
+fn test_treemap_deserialize_portable() {
+    use croaring::treemap::PortableSerializer;
+
+    // The C++ implementation writes the portable format on little-endian platforms
+    let buffer = read_file("tests/data/testcpp.bin").unwrap();
+    let treemap = Treemap::deserialize(&buffer).unwrap();
+
+    for i in 100..1000 {
+        assert!(treemap.contains(i));
+    }
+    assert!(treemap.contains(std::u32::MAX as u64));
+    assert!(treemap.contains(std::u64::MAX));
+    assert_eq!(treemap.cardinality(), 902);
+
+    assert_eq!(treemap.serialize().unwrap(), buffer);
+
+    for len in 0..buffer.len() {
+        assert!(Treemap::deserialize(&buffer[..len]).is_err());
+    }
+}
+
 #[test]
 fn test_treemap_deserialize_jvm() {
     match read_file("tests/data/testjvm.bin") {
         Ok(buffer) => {
             use croaring::treemap::JvmSerializer;
 
-            let treemap = Treemap::deserialize(&buffer).unwrap();
+            let treemap = Treemap::deserialize(&buffer).unwrap();
+
+            for i in 100..1000 {
+                assert!(treemap.contains(i));
+            }
+
+            assert!(treemap.contains(std::u32::MAX as u64));
+            assert!(treemap.contains(std::u64::MAX));
+        }
+        Err(err) => assert!(false, "Cannot read test file {}", err),
+    }
+}
+
+#[test]
+fn treemap_serialized_size_matches_every_format() {
+    use croaring::treemap::{JvmSerializer, NativeSerializer, PortableSerializer};
+
+    // Sparse, dense and run containers spread over several high keys
+    let mut treemap: Treemap = (0..100).chain((1 << 32)..(1 << 32) + 10_000).collect();
+    treemap.add_range((7 << 32) + 5..(7 << 32) + 500_000);
+    treemap.add(u64::MAX);
+    treemap.run_optimize();
+    assert_eq!(treemap.map.len(), 4);
+
+    for treemap in [Treemap::create(), Treemap::of(&[1 << 40]), treemap] {
+        let portable = PortableSerializer::serialize(&treemap).unwrap();
+        assert_eq!(portable.len(), PortableSerializer::get_serialized_size_in_bytes(&treemap));
+
+        let native = NativeSerializer::serialize(&treemap).unwrap();
+        assert_eq!(native.len(), NativeSerializer::get_serialized_size_in_bytes(&treemap));
+
+        let jvm = JvmSerializer::serialize(&treemap).unwrap();
+        assert_eq!(jvm.len(), JvmSerializer::get_serialized_size_in_bytes(&treemap));
+    }
+}
+
+#[test]
+fn operation_cardinalities_empty_and_disjoint() {
+    let empty = Bitmap::create();
+    let bitmap1 = Bitmap::of(&[1, 2, 3, 100_000]);
+    let bitmap2 = Bitmap::from_range(200_000..300_000);
+
+    for (a, b) in [
+        (&empty, &empty),
+        (&empty, &bitmap1),
+        (&bitmap1, &empty),
+        (&bitmap1, &bitmap2),
+        (&bitmap2, &bitmap1),
+    ] {
+        assert_eq!(a.and_cardinality(b), a.and(b).cardinality());
+        assert_eq!(a.or_cardinality(b), a.or(b).cardinality());
+        assert_eq!(a.andnot_cardinality(b), a.andnot(b).cardinality());
+        assert_eq!(a.xor_cardinality(b), a.xor(b).cardinality());
+    }
+
+    assert_eq!(bitmap1.and_cardinality(&bitmap2), 0);
+    assert_eq!(bitmap1.or_cardinality(&bitmap2), 100_004);
+    assert_eq!(bitmap1.andnot_cardinality(&bitmap2), 4);
+    assert_eq!(bitmap1.xor_cardinality(&bitmap2), 100_004);
+}
+
+#[test]
+fn run_optimize_reduces_serialized_size() {
+    let mut bitmap: Bitmap = (0..1_000_000).collect();
+    let original = bitmap.clone();
+    let old_size = bitmap.get_serialized_size_in_bytes();
+
+    assert!(bitmap.run_optimize());
+    assert!(bitmap.get_serialized_size_in_bytes() < old_size);
+    assert_eq!(bitmap, original);
+
+    assert!(bitmap.remove_run_compression());
+    assert_eq!(bitmap.get_serialized_size_in_bytes(), old_size);
+    assert_eq!(bitmap, original);
+}
+
+#[test]
+fn optimized_constructors_match_unoptimized() {
+    for range in [0..0, 5..6, 100..100_000, 0..u32::MAX] {
+        let plain = Bitmap::from_range(range.clone());
+        let optimized = Bitmap::from_range_optimized(range.clone());
+        assert_eq!(optimized, plain, "range {:?}", range);
+        assert!(optimized.get_serialized_size_in_bytes() <= plain.get_serialized_size_in_bytes());
+    }
+
+    // Runs, a dense container and a sparse container
+    let elements: Vec<u32> = (0..100_000)
+        .chain((200_000..300_000).step_by(3))
+        .chain((1_000_000..1_000_100).step_by(7))
+        .collect();
+    let plain = Bitmap::of(&elements);
+    let optimized = Bitmap::of_optimized(&elements);
+    assert_eq!(optimized, plain);
+    assert!(optimized.get_serialized_size_in_bytes() < plain.get_serialized_size_in_bytes());
+
+    assert!(Bitmap::of_optimized(&[]).is_empty());
+}
+
+#[test]
+fn equals_ignores_container_kinds() {
+    // Runs, a dense (bitset) container and a sparse (array) container
+    let mut plain: Bitmap = (0..200_000).chain((300_000..360_000).step_by(2)).collect();
+    plain.add_many(&[400_000, 400_010, u32::MAX]);
+    let mut optimized = plain.clone();
+    assert!(optimized.run_optimize());
+    assert!(optimized.statistics().n_run_containers > plain.statistics().n_run_containers);
+
+    assert!(plain.equals(&optimized));
+    assert!(optimized.equals(&plain));
+    assert_eq!(plain, optimized);
+
+    let mut rebuilt = Bitmap::from_range(0..200_000);
+    rebuilt.extend((300_000..360_000).step_by(2));
+    rebuilt.add_many(&[u32::MAX, 400_010, 400_000]);
+    assert!(rebuilt.equals(&optimized));
+
+    optimized.remove(199_999);
+    assert!(!plain.equals(&optimized));
+    assert_ne!(plain, optimized);
+    optimized.add(199_999);
+    optimized.remove(u32::MAX);
+    assert!(!optimized.equals(&plain));
+}
+
+#[test]
+fn shrink_to_fit_after_removal() {
+    let mut bitmap: Bitmap = (0..1_000_000).map(|i| i * 20).collect();
+    for i in 0..1_000_000 {
+        if i % 10 != 0 {
+            bitmap.remove(i * 20);
+        }
+    }
+    let expected = bitmap.clone();
+    let size = bitmap.get_serialized_size_in_bytes();
+
+    assert!(bitmap.shrink_to_fit() > 0);
+    assert_eq!(bitmap.shrink_to_fit(), 0);
+    assert_eq!(bitmap.get_serialized_size_in_bytes(), size);
+    assert_eq!(bitmap, expected);
+    assert_eq!(bitmap.cardinality(), 100_000);
+}
+
+#[test]
+fn copy_on_write_clone_mutation() {
+    let mut original: Bitmap = (0..100_000).chain(500_000..500_010).collect();
+    unsafe { original.set_copy_on_write(true) };
+    let expected = original.to_vec();
+
+    let mut copy = original.clone();
+    copy.remove_range(0..50_000);
+    copy.add_range(1_000_000..1_000_100);
+    copy.remove(500_005);
+    copy.run_optimize();
+
+    assert_eq!(original.to_vec(), expected);
+    assert_eq!(copy.cardinality(), 50_000 + 9 + 100);
+
+    let mut second_copy = original.clone();
+    drop(original);
+    second_copy.add(7_000_000);
+    assert_eq!(second_copy.cardinality(), expected.len() as u64 + 1);
+    assert!(second_copy.iter().take(expected.len()).eq(expected.iter().copied()));
+}
+
+#[test]
+fn bitmap_iterator_count() {
+    let bitmap: Bitmap = (0..100_000).chain(1_000_000..1_000_010).collect();
+    assert_eq!(bitmap.iter().count() as u64, bitmap.cardinality());
+    assert_eq!(bitmap.clone().into_iter().count() as u64, bitmap.cardinality());
+    assert_eq!(Bitmap::create().iter().count(), 0);
+
+    let mut iter = bitmap.iter();
+    iter.next();
+    iter.next_back();
+    assert_eq!(iter.count() as u64, bitmap.cardinality() - 2);
+
+    let mut iter = bitmap.iter();
+    let mut buf = [0; 1000];
+    iter.next_many(&mut buf);
+    assert_eq!(iter.count() as u64, bitmap.cardinality() - 1000);
+}
+
+#[test]
+fn bitmap_iterator_reset() {
+    let bitmap: Bitmap = (0..100_000).chain(1_000_000..1_000_010).collect();
+    let expected = bitmap.to_vec();
+
+    let mut iter = bitmap.iter();
+    let first_pass: Vec<u32> = iter.by_ref().collect();
+    assert_eq!(first_pass, expected);
+    assert_eq!(iter.len(), 0);
+
+    iter.reset();
+    assert_eq!(iter.len(), expected.len());
+    let second_pass: Vec<u32> = iter.by_ref().collect();
+    assert_eq!(second_pass, expected);
+
+    // Reset part way through, from both ends
+    iter.reset();
+    let mut buf = [0; 100];
+    iter.next_many(&mut buf);
+    iter.next_back();
+    iter.reset();
+    assert!(iter.eq(expected.iter().copied()));
+}
+
+#[test]
+fn bitmap_iterators_are_fused() {
+    fn assert_fused<I: std::iter::FusedIterator>(mut iter: I) {
+        while iter.next().is_some() {}
+        for _ in 0..3 {
+            assert!(iter.next().is_none());
+        }
+    }
+
+    let bitmap: Bitmap = (0..10).chain([100_000, u32::MAX]).collect();
+
+    assert_fused(bitmap.iter());
+    assert_fused(bitmap.iter_range(5..200_000));
+    assert_fused(bitmap.iter_runs());
+    assert_fused(bitmap.containers());
+    assert_fused(bitmap.clone().into_iter());
+    assert_fused(Bitmap::create().iter());
+
+    // Exhausting from the back also stays exhausted at the front
+    let mut iter = bitmap.iter();
+    while iter.next_back().is_some() {}
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.next(), None);
+
+    let mut iter = bitmap.clone().into_iter();
+    while iter.next_back().is_some() {}
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+}
+
+#[test]
+fn bitmap_iter_runs_full_range() {
+    let bitmap = Bitmap::from_range(..);
+    let runs: Vec<(u32, u32)> = bitmap.iter_runs().collect();
+    assert_eq!(runs, [(0, u32::MAX), (u32::MAX, 1)]);
+
+    let bitmap = Bitmap::from_range(10..);
+    let runs: Vec<(u32, u32)> = bitmap.iter_runs().collect();
+    assert_eq!(runs, [(10, u32::MAX - 9)]);
+}
+
+#[test]
+fn bitmap_containers_keep_run_containers() {
+    // Runs, a dense (bitset) container and a sparse (array) container
+    let mut bitmap: Bitmap = (0..200_000)
+        .chain((300_000..340_000).step_by(2))
+        .chain([1_000_000, 1_000_007])
+        .collect();
+    assert!(bitmap.run_optimize());
+    let statistics = bitmap.statistics();
+
+    let containers: Vec<(u16, Bitmap)> = bitmap.containers().collect();
+    assert_eq!(containers.len() as u32, statistics.n_containers);
+
+    let sum = |count: fn(&Bitmap) -> u32| -> u32 {
+        containers.iter().map(|(_, piece)| count(piece)).sum()
+    };
+    assert_eq!(sum(|piece| piece.statistics().n_run_containers), statistics.n_run_containers);
+    assert_eq!(sum(|piece| piece.statistics().n_bitset_containers), statistics.n_bitset_containers);
+    assert_eq!(sum(|piece| piece.statistics().n_array_containers), statistics.n_array_containers);
+
+    // Every container of a full bitmap is a single run
+    let full = Bitmap::from_range(..);
+    let mut count = 0;
+    for (high_bits, piece) in full.containers() {
+        assert_eq!(high_bits as u32, count);
+        assert_eq!(piece.statistics().n_run_containers, 1);
+        count += 1;
+    }
+    assert_eq!(count, 1 << 16);
+}
+
+#[test]
+fn bitmap_iterators_from_both_ends_never_overlap() {
+    let bitmap: Bitmap = (0..10_000).chain(100_000..100_500).chain([u32::MAX]).collect();
+    let mut buf = [0; 7];
+
+    let mut seen = Vec::new();
+    let mut iter = bitmap.iter();
+    for step in 0.. {
+        let before = seen.len();
+        match step % 4 {
+            0 => seen.extend(iter.next()),
+            1 => seen.extend(iter.next_back()),
+            2 => {
+                let n = iter.next_many(&mut buf);
+                seen.extend_from_slice(&buf[..n]);
+            }
+            _ => {
+                let n = iter.next_many_back(&mut buf);
+                seen.extend_from_slice(&buf[..n]);
+            }
+        }
+        if seen.len() == before {
+            break;
+        }
+    }
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.next_many(&mut buf), 0);
+    assert_eq!(seen.len() as u64, bitmap.cardinality());
+    seen.sort_unstable();
+    assert_eq!(seen, bitmap.to_vec());
+
+    let mut seen = Vec::new();
+    let mut iter = bitmap.clone().into_iter();
+    loop {
+        let n = iter.next_many(&mut buf);
+        seen.extend_from_slice(&buf[..n]);
+        let back = iter.next_back();
+        seen.extend(back);
+        if n == 0 && back.is_none() {
+            break;
+        }
+    }
+    assert_eq!(seen.len() as u64, bitmap.cardinality());
+    seen.sort_unstable();
+    assert_eq!(seen, bitmap.to_vec());
+}
+
+#[test]
+fn statistics_after_run_optimize() {
+    let mut bitmap: Bitmap = (0..100_000)
+        .chain(200_000..200_100)
+        .chain([300_000, 300_002, 300_004])
+        .collect();
+
+    let before = bitmap.statistics();
+    assert_eq!(before.n_containers, 4);
+    assert_eq!(before.n_array_containers, 2);
+    assert_eq!(before.n_bitset_containers, 2);
+    assert_eq!(before.n_run_containers, 0);
+    assert_eq!(before.cardinality, 100_103);
+    assert_eq!(bitmap.container_type_counts(), (2, 0, 2));
+
+    assert!(bitmap.run_optimize());
+    let after = bitmap.statistics();
+
+    assert_eq!(after.n_containers, before.n_containers);
+    assert_eq!(after.n_run_containers, 3);
+    assert_eq!(after.n_bitset_containers, 0);
+    // The sparse container doesn't benefit from run encoding
+    assert_eq!(after.n_array_containers, 1);
+    assert!(after.n_bytes_run_containers < before.n_bytes_bitset_containers);
+    assert_eq!(after.cardinality, before.cardinality);
+    assert_eq!(bitmap.container_type_counts(), (1, 3, 0));
+    assert_ne!(before, after);
+
+    assert!(bitmap.remove_run_compression());
+    assert_eq!(bitmap.statistics().n_run_containers, 0);
+    assert_eq!(bitmap.container_type_counts(), (2, 0, 2));
+}
+
+#[test]
+fn bitmap_deserialize_wrong_format() {
+    let bitmaps = [
+        Bitmap::create(),
+        Bitmap::of(&[1, 2, 3]),
+        (0..100_000).collect(),
+        Bitmap::of(&[0, 70_000, u32::MAX]),
+    ];
+
+    for bitmap in &bitmaps {
+        let portable = bitmap.serialize::<Portable>();
+        let native = bitmap.serialize::<Native>();
+        let frozen = bitmap.serialize::<Frozen>();
+
+        assert!(Bitmap::try_deserialize::<Native>(&portable).is_none());
+        assert!(Bitmap::try_deserialize::<Frozen>(&portable).is_none());
+        assert!(Bitmap::try_deserialize::<Portable>(&native).is_none());
+        assert!(Bitmap::try_deserialize::<Frozen>(&native).is_none());
+        assert!(Bitmap::try_deserialize::<Portable>(&frozen).is_none());
+        assert!(Bitmap::try_deserialize::<Native>(&frozen).is_none());
+
+        assert!(Bitmap::try_deserialize::<Native>(&native[..native.len() - 1]).is_none());
+    }
+}
+
+#[test]
+fn bitmap_try_from_bytes() {
+    let mut optimized: Bitmap = (0..100_000).chain(200_000..200_010).collect();
+    optimized.run_optimize();
+    let bitmaps = [
+        Bitmap::create(),
+        Bitmap::of(&[1, 2, 3]),
+        (0..100_000).collect(),
+        Bitmap::of(&[0, 70_000, u32::MAX]),
+        optimized,
+    ];
+
+    for bitmap in &bitmaps {
+        let portable = bitmap.serialize::<Portable>();
+
+        let deserialized: Bitmap = portable[..].try_into().unwrap();
+        assert_eq!(&deserialized, bitmap);
+
+        for len in 0..portable.len() {
+            assert_eq!(Bitmap::try_from(&portable[..len]), Err(DeserializeError::Truncated));
+        }
+    }
+
+    let garbage = [0xFF; 64];
+    let error = Bitmap::try_from(&garbage[..]).unwrap_err();
+    assert_eq!(error, DeserializeError::Invalid);
+
+    let error: Box<dyn std::error::Error> = Box::new(error);
+    assert_eq!(error.to_string(), "invalid portable roaring bitmap");
+}
+
+#[test]
+fn bitmap_clear_and_repopulate() {
+    let mut bitmap = Bitmap::create();
+
+    for round in 0..10u32 {
+        let start = round * 50_000;
+        bitmap.add_range(start..start + 100_000);
+        bitmap.add_many(&[1, 3, 5, u32::MAX - round]);
+        if round % 2 == 0 {
+            bitmap.run_optimize();
+        }
+        assert!(bitmap.cardinality() >= 100_000);
+
+        bitmap.clear();
+        assert_eq!(bitmap.cardinality(), 0);
+        assert!(bitmap.is_empty());
+        assert_eq!(bitmap.minimum(), None);
+        assert_eq!(bitmap.iter().next(), None);
+        assert_eq!(bitmap, Bitmap::create());
+    }
+
+    bitmap.add(7);
+    assert_eq!(bitmap.to_vec(), [7]);
+}
+
+#[test]
+fn bitmap_retain_even() {
+    let mut bitmap: Bitmap = (0..1000)
+        .chain(100_000..200_000)
+        .chain([3_000_001, 3_000_002, u32::MAX - 1, u32::MAX])
+        .collect();
+    bitmap.run_optimize();
+    let expected: Vec<u32> = bitmap.iter().filter(|value| value % 2 == 0).collect();
+
+    let mut visited = Vec::new();
+    bitmap.retain(|value| {
+        visited.push(value);
+        value % 2 == 0
+    });
+
+    assert_eq!(bitmap, expected);
+    assert_eq!(visited.len(), 1000 + 100_000 + 4);
+    assert!(visited.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let before = bitmap.clone();
+    bitmap.retain(|_| true);
+    assert_eq!(bitmap, before);
+
+    bitmap.retain(|_| false);
+    assert!(bitmap.is_empty());
+}
+
+#[test]
+fn bitmap_swap() {
+    let mut optimized = Bitmap::from_range(0..1_000_000);
+    optimized.run_optimize();
+    let mut current = Bitmap::of(&[1, 2, 70_000, u32::MAX]);
+    let mut next = optimized.clone();
+
+    for _ in 0..3 {
+        current.swap(&mut next);
+        assert_eq!(current, optimized);
+        assert_eq!(next, [1, 2, 70_000, u32::MAX]);
+
+        // Both bitmaps remain independently usable after swapping
+        current.add(5_000_000);
+        next.add(3);
+        current.remove(5_000_000);
+        next.remove(3);
+
+        std::mem::swap(&mut current, &mut next);
+        current.swap(&mut next);
+        current.swap(&mut next);
+    }
+
+    let mut empty = Bitmap::create();
+    empty.swap(&mut current);
+    assert!(current.is_empty());
+    assert_eq!(empty, [1, 2, 70_000, u32::MAX]);
+}
+
+#[test]
+fn bitmap_reserve_then_insert_many() {
+    let values: Vec<u32> = (0..2_000).map(|i| i * 40_503).collect();
+
+    let mut reserved = Bitmap::of(&[7, 70_000, u32::MAX]);
+    reserved.reserve(2_000);
+    assert_eq!(reserved, [7, 70_000, u32::MAX]);
+
+    // Reserving less than is already allocated does nothing
+    reserved.reserve(0);
+    assert_eq!(reserved, [7, 70_000, u32::MAX]);
+
+    let mut unreserved = Bitmap::of(&[7, 70_000, u32::MAX]);
+    for &value in &values {
+        reserved.add(value);
+        unreserved.add(value);
+    }
+    assert_eq!(reserved, unreserved);
+    assert_eq!(reserved.cardinality(), unreserved.cardinality());
+
+    // The original containers survived the move into the larger allocation
+    assert!(reserved.contains(7));
+    assert!(reserved.contains(70_000));
+    assert!(reserved.contains(u32::MAX));
+
+    let mut with_capacity = Bitmap::with_capacity(usize::MAX);
+    with_capacity.add_many(&values);
+    assert_eq!(with_capacity, values);
+}
+
+#[test]
+fn bitmap_reserve_on_empty_bitmap() {
+    let values: Vec<u32> = (0..100).map(|i| i << 16).collect();
+
+    // A new bitmap has no container arrays allocated
+    let mut created = Bitmap::create();
+    created.reserve(10);
+    assert!(created.is_empty());
+    created.add_many(&values);
+    assert_eq!(created, values);
+
+    let mut zero_capacity = Bitmap::with_capacity(0);
+    zero_capacity.reserve(0);
+    zero_capacity.reserve(1);
+    assert!(zero_capacity.is_empty());
+    zero_capacity.add_many(&values);
+    assert_eq!(zero_capacity, values);
+
+    // Allocated, but still without containers
+    let mut with_capacity = Bitmap::with_capacity(16);
+    with_capacity.reserve(1_000);
+    assert!(with_capacity.is_empty());
+    for &value in &values {
+        with_capacity.add(value);
+    }
+    assert_eq!(with_capacity, values);
+
+    // Clearing frees the container arrays
+    let mut cleared = Bitmap::of(&values);
+    cleared.clear();
+    cleared.reserve(1_000);
+    assert!(cleared.is_empty());
+    cleared.add_many(&values);
+    assert_eq!(cleared, values);
+
+    let mut copy_on_write = Bitmap::create();
+    unsafe { copy_on_write.set_copy_on_write(true) };
+    copy_on_write.reserve(10);
+    assert!(copy_on_write.get_copy_on_write());
+}
+
+#[test]
+fn bitmap_clone_from() {
+    let sources: [Bitmap; 4] = [
+        (0..100_000).collect(),
+        Bitmap::of(&[1, 70_000, u32::MAX]),
+        Bitmap::create(),
+        (200_000..300_000).step_by(2).collect(),
+    ];
+
+    let mut bitmap: Bitmap = (0..10).collect();
+    for source in sources.iter().chain(sources.iter().rev()) {
+        bitmap.clone_from(source);
+        assert_eq!(&bitmap, source);
+        assert_eq!(bitmap.cardinality(), source.cardinality());
+    }
+
+    // The copy is independent of its source
+    let mut copy = Bitmap::create();
+    copy.clone_from(&sources[1]);
+    copy.add(5);
+    assert!(!sources[1].contains(5));
+}
+
+#[test]
+fn default_is_empty() {
+    #[derive(Default)]
+    struct Index {
+        bitmap: Bitmap,
+        treemap: Treemap,
+    }
+
+    let mut index = Index::default();
+    assert!(index.bitmap.is_empty());
+    assert!(index.treemap.is_empty());
+    assert_eq!(index.bitmap, Bitmap::create());
+    assert_eq!(index.treemap, Treemap::create());
+
+    index.bitmap.add(1);
+    index.treemap.add(u64::MAX);
+    assert_eq!(index.bitmap.cardinality(), 1);
+    assert_eq!(index.treemap.cardinality(), 1);
+}
+
+#[test]
+fn bitmap_is_full_after_flip() {
+    let mut bitmap = Bitmap::create();
+    assert!(!bitmap.is_full());
+
+    bitmap.flip_inplace(0..=u32::MAX);
+    assert!(bitmap.is_full());
+    assert!(bitmap.flip(..).is_empty());
+
+    bitmap.run_optimize();
+    assert!(bitmap.is_full());
+
+    bitmap.remove(0);
+    assert!(!bitmap.is_full());
+    assert!(!Bitmap::from_range(0..u32::MAX).is_full());
+}
+
+#[test]
+fn bitmap_closed_ranges_at_u32_max() {
+    let mut bitmap = Bitmap::create();
+    bitmap.add_range_closed(0, u32::MAX);
+    assert!(bitmap.is_full());
+    assert!(bitmap.contains_range_closed(0, u32::MAX));
+    assert_eq!(bitmap, Bitmap::from_range(..));
+
+    bitmap.remove_range_closed(u32::MAX - 1, u32::MAX);
+    assert!(!bitmap.is_full());
+    assert!(!bitmap.contains_range_closed(0, u32::MAX));
+    assert!(bitmap.contains_range_closed(0, u32::MAX - 2));
+    assert_eq!(bitmap.maximum(), Some(u32::MAX - 2));
+
+    bitmap.add_range_closed(u32::MAX, u32::MAX);
+    assert_eq!(bitmap.cardinality(), u64::from(u32::MAX));
+    assert!(!bitmap.contains(u32::MAX - 1));
+
+    bitmap.remove_range_closed(0, u32::MAX);
+    assert!(bitmap.is_empty());
+}
+
+#[test]
+fn bitmap_insert_range_count_partially_present() {
+    let mut bitmap: Bitmap = (0..1_000).step_by(2).chain(70_000..70_100).collect();
+    let mut expected_cardinality = bitmap.cardinality();
+
+    // Half of 0..1000 is already present
+    let added = bitmap.insert_range_count(0..1_000);
+    assert_eq!(added, 500);
+    expected_cardinality += added;
+
+    // Overlaps the existing run at both ends of a container boundary
+    let added = bitmap.insert_range_count(65_000..=70_049);
+    assert_eq!(added, 70_050 - 65_000 - 50);
+    expected_cardinality += added;
+
+    assert_eq!(bitmap.insert_range_count(500..800), 0);
+    assert_eq!(bitmap.insert_range_count(..), (1 << 32) - expected_cardinality);
+    assert!(bitmap.is_full());
+
+    let mut bitmap = Bitmap::of(&[5]);
+    assert!(!bitmap.contains_then_add(6));
+    assert!(bitmap.contains_then_add(5));
+    assert!(bitmap.contains_then_add(6));
+    assert_eq!(bitmap, [5, 6]);
+}
+
+#[test]
+fn bitmap_add_range_checked_partial_overlaps() {
+    let mut bitmap = Bitmap::of(&[10, 11, 12, 65_535, 65_536, 200_000]);
+    let mut total = bitmap.cardinality();
+
+    // Overlaps the start, the end, and the middle of existing values
+    let ranges = [
+        (5..11, 5),
+        (12..20, 7),
+        (65_530..65_540, 8),
+        (0..200_001, 200_001 - 26),
+    ];
+    for (range, added) in ranges {
+        let before = bitmap.range_cardinality(range.clone());
+        assert_eq!(bitmap.add_range_checked(range.clone()), added, "range {:?}", range);
+        assert_eq!(bitmap.range_cardinality(range.clone()), before + added);
+        total += added;
+        assert_eq!(bitmap.cardinality(), total);
+    }
+
+    assert_eq!(bitmap.add_range_checked(100..200), 0);
+    assert_eq!(bitmap.add_range_checked(300_000..=300_000), 1);
+    assert_eq!(bitmap.add_range_checked(u32::MAX..), 1);
+    assert_eq!(bitmap.add_range_checked(5..1), 0);
+    assert_eq!(bitmap.cardinality(), total + 2);
+}
+
+#[test]
+fn bitmap_empty_ref_in_operations() {
+    let bitmap = Bitmap::of(&[1, 70_000, u32::MAX]);
+    let empty = Bitmap::empty_ref();
+
+    assert!(empty.is_empty());
+    assert!(std::ptr::eq(empty, Bitmap::empty_ref()));
+    assert_eq!(bitmap.or(empty), bitmap);
+    assert_eq!(empty.or(&bitmap), bitmap);
+    assert!(bitmap.and(empty).is_empty());
+    assert!(empty.and(&bitmap).is_empty());
+    assert_eq!(bitmap.andnot(empty), bitmap);
+    assert!(!bitmap.intersect(empty));
+    assert!(empty.is_subset(&bitmap));
+    assert_eq!(Bitmap::fast_or(&[empty, &bitmap, empty]), bitmap);
+
+    let threads: Vec<_> = (0..4u32)
+        .map(|i| {
+            std::thread::spawn(move || {
+                let other = Bitmap::from_range(i * 10..i * 10 + 5);
+                assert_eq!(other.or(Bitmap::empty_ref()), other);
+                Bitmap::empty_ref().and_cardinality(&other)
+            })
+        })
+        .collect();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), 0);
+    }
+    assert!(Bitmap::empty_ref().is_empty());
+}
+
+#[test]
+fn bitmap_dense_bitset_spans_several_words() {
+    let bitmap = Bitmap::of(&[0, 63, 64, 127, 128, 200, 1000]);
+    let words = bitmap.to_dense_bitset();
+
+    assert_eq!(words.len(), 1000 / 64 + 1);
+    assert_eq!(words[0], 1 | 1 << 63);
+    assert_eq!(words[1], 1 | 1 << 63);
+    assert_eq!(words[2], 1);
+    assert_eq!(words[3], 1 << (200 - 192));
+    assert!(words[4..15].iter().all(|&word| word == 0));
+    assert_eq!(words[15], 1 << (1000 - 960));
+    assert_eq!(Bitmap::from_dense_bitset(&words), bitmap);
+
+    let full_words = Bitmap::from_dense_bitset(&[u64::MAX; 3]);
+    assert_eq!(full_words, Bitmap::from_range(0..192));
+    assert_eq!(full_words.to_dense_bitset(), [u64::MAX; 3]);
+    assert!(Bitmap::from_dense_bitset(&[0, 0]).is_empty());
+}
+
+#[test]
+fn bitmap_split_at() {
+    let bitmap: Bitmap = [0, 7, 64, 65_535, 65_536, 1_000_000, u32::MAX]
+        .into_iter()
+        .chain(100..200)
+        .collect();
+
+    let check = |pivot: u32, expected_lower: u64| {
+        let (lower, upper) = bitmap.split_at(pivot);
+        assert_eq!(lower.cardinality(), expected_lower, "pivot {}", pivot);
+        assert!(lower.iter().all(|value| value < pivot));
+        assert!(upper.iter().all(|value| value >= pivot));
+        assert!(!lower.intersect(&upper));
+        assert_eq!(lower.or(&upper), bitmap);
+    };
+
+    // Pivot on a present value goes to the upper half
+    check(64, 2);
+    check(65_536, 2 + 1 + 100 + 1);
+    // Pivot on an absent value
+    check(63, 2);
+    check(150, 2 + 1 + 50);
+    check(999_999, 2 + 1 + 100 + 2);
+    // Extremes
+    check(0, 0);
+    check(1, 1);
+    check(u32::MAX, bitmap.cardinality() - 1);
+
+    let (lower, upper) = Bitmap::create().split_at(5);
+    assert!(lower.is_empty() && upper.is_empty());
+}
+
+#[test]
+fn bitmap_operators_owned_and_borrowed() {
+    // `b` has more containers than `a`, so the owned operands may be swapped
+    let a = Bitmap::of(&[1, 5, 70_000, 200_000]);
+    let b = Bitmap::of(&[5, 200_000, 300_000, 400_000, 500_000]);
+
+    macro_rules! check {
+        ($op:tt, $lhs:expr, $rhs:expr, $expected:expr) => {
+            let expected: &[u32] = &$expected;
+            assert_eq!((&$lhs $op &$rhs).to_vec(), expected);
+            assert_eq!((&$lhs $op $rhs.clone()).to_vec(), expected);
+            assert_eq!(($lhs.clone() $op &$rhs).to_vec(), expected);
+            assert_eq!(($lhs.clone() $op $rhs.clone()).to_vec(), expected);
+        };
+    }
+
+    check!(&, a, b, [5, 200_000]);
+    check!(&, b, a, [5, 200_000]);
+    check!(|, a, b, [1, 5, 70_000, 200_000, 300_000, 400_000, 500_000]);
+    check!(|, b, a, [1, 5, 70_000, 200_000, 300_000, 400_000, 500_000]);
+    check!(^, a, b, [1, 70_000, 300_000, 400_000, 500_000]);
+    check!(^, b, a, [1, 70_000, 300_000, 400_000, 500_000]);
+    check!(-, a, b, [1, 70_000]);
+    check!(-, b, a, [300_000, 400_000, 500_000]);
+
+    // The borrowed operands are left untouched
+    assert_eq!(a.to_vec(), [1, 5, 70_000, 200_000]);
+    assert_eq!(b.to_vec(), [5, 200_000, 300_000, 400_000, 500_000]);
+}
+
+#[test]
+fn bitmap_try_add_matches_add_checked() {
+    let values = [5, 1, 5, 70_000, u32::MAX, 1, 70_000, 0];
+
+    let mut checked = Bitmap::create();
+    let mut fallible = Bitmap::try_create_with_capacity(4).unwrap();
+    for &value in &values {
+        assert_eq!(fallible.try_add(value), Ok(checked.add_checked(value)));
+    }
+    assert_eq!(fallible, checked);
+
+    let mut many = Bitmap::create();
+    assert_eq!(many.try_add_many(&values), Ok(()));
+    assert_eq!(many, checked);
+
+    assert_eq!(BitmapError::AllocationFailed.to_string(), "roaring bitmap allocation failed");
+}
+
+#[test]
+fn bitmap_serialize_into_writer() {
+    let bitmaps: [Bitmap; 3] = [
+        Bitmap::create(),
+        (0..100_000).chain([u32::MAX]).collect(),
+        Bitmap::of(&[1, 70_000, 1_000_000]),
+    ];
+
+    let mut writer: Vec<u8> = Vec::new();
+    let mut sizes = Vec::new();
+    for bitmap in &bitmaps {
+        let written = bitmap.serialize_into_writer(&mut writer).unwrap();
+        assert_eq!(written, bitmap.get_serialized_size_in_bytes());
+        sizes.push(written);
+    }
+    assert_eq!(writer.len(), sizes.iter().sum::<usize>());
+
+    let mut rest = &writer[..];
+    for (bitmap, size) in bitmaps.iter().zip(sizes) {
+        assert_eq!(&Bitmap::deserialize::<Portable>(&rest[..size]), bitmap);
+        rest = &rest[size..];
+    }
+}
+
+#[test]
+fn bitmap_serialize_into_reused_buffer() {
+    let large: Bitmap = (0..100_000).chain([u32::MAX]).collect();
+    let small = Bitmap::of(&[1, 70_000]);
+    let mut buffer = Vec::new();
+
+    let serialized = large.serialize_into::<Portable>(&mut buffer).to_vec();
+    assert_eq!(serialized, large.serialize::<Portable>());
+    let capacity = buffer.capacity();
+
+    let serialized = small.serialize_into::<Portable>(&mut buffer);
+    assert_eq!(serialized, small.serialize::<Portable>());
+    assert_eq!(Bitmap::deserialize::<Portable>(serialized), small);
+    assert_eq!(buffer.capacity(), capacity);
+
+    let serialized = large.serialize_into::<Native>(&mut buffer);
+    assert_eq!(Bitmap::deserialize::<Native>(serialized), large);
+}
+
+#[test]
+fn bitmap_to_vec_into_reused_buffer() {
+    let large: Bitmap = (0..100_000).chain([u32::MAX]).collect();
+    let small = Bitmap::of(&[1, 70_000]);
+    let mut buffer = Vec::new();
+
+    large.to_vec_into(&mut buffer);
+    assert_eq!(buffer, large.to_vec());
+    let capacity = buffer.capacity();
+    let ptr = buffer.as_ptr();
+
+    small.to_vec_into(&mut buffer);
+    assert_eq!(buffer, small.to_vec());
+    assert_eq!(buffer.capacity(), capacity);
+    assert_eq!(buffer.as_ptr(), ptr);
+
+    Bitmap::create().to_vec_into(&mut buffer);
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.capacity(), capacity);
+}
+
+#[test]
+fn bitmap_deserialize_from_reader() {
+    use std::io::{Cursor, ErrorKind};
+
+    let mut optimized: Bitmap = (0..100_000).chain(200_000..200_010).collect();
+    optimized.run_optimize();
+    let many_runs: Bitmap = (0..5u32).flat_map(|i| i * 70_000..i * 70_000 + 3).chain(0..10).collect();
+    let mut many_runs_optimized = many_runs.clone();
+    many_runs_optimized.run_optimize();
+    let bitmaps: [Bitmap; 5] = [
+        Bitmap::create(),
+        (0..100_000).chain([u32::MAX]).collect(),
+        optimized,
+        many_runs,
+        many_runs_optimized,
+    ];
+
+    let mut buffer: Vec<u8> = Vec::new();
+    for bitmap in &bitmaps {
+        bitmap.serialize_into_writer(&mut buffer).unwrap();
+    }
+
+    let mut reader = Cursor::new(&buffer);
+    for bitmap in &bitmaps {
+        assert_eq!(&Bitmap::deserialize_from_reader(&mut reader).unwrap(), bitmap);
+    }
+    assert_eq!(reader.position() as usize, buffer.len());
+
+    for bitmap in &bitmaps {
+        let serialized = bitmap.serialize::<Portable>();
+        for len in 0..serialized.len() {
+            let err = Bitmap::deserialize_from_reader(&mut &serialized[..len]).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        }
+    }
+
+    let err = Bitmap::deserialize_from_reader(&mut &[1u8, 2, 3, 4][..]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn treemap_statistics_after_run_optimize() {
+    let mut treemap: Treemap = (0..100_000)
+        .chain((1 << 32)..(1 << 32) + 70_000)
+        .chain([(2 << 32) + 1, (2 << 32) + 3])
+        .chain((5 << 32) + 1000..(5 << 32) + 5000)
+        .collect();
+
+    let before = treemap.statistics();
+    assert_eq!(before.n_run_containers, 0);
+    assert_eq!(before.cardinality, treemap.cardinality());
+
+    assert!(treemap.run_optimize());
+    let after = treemap.statistics();
+    assert_eq!(after.n_containers, before.n_containers);
+    assert_eq!(after.n_run_containers, after.n_containers - 1);
+    assert_eq!(after.n_array_containers, 1);
+    assert_eq!(after.cardinality, treemap.cardinality());
+    assert_eq!(
+        u64::from(after.n_values_array_containers + after.n_values_run_containers),
+        treemap.cardinality()
+    );
+
+    // Every range-heavy inner bitmap was optimized, not just the first
+    for (&key, bitmap) in &treemap.map {
+        if key != 2 {
+            assert!(bitmap.statistics().n_run_containers > 0);
+        }
+    }
+
+    assert!(treemap.remove_run_compression());
+    assert_eq!(treemap.statistics().n_run_containers, 0);
+}
+
+#[test]
+fn treemap_jaccard_index_partially_shared_keys() {
+    // High keys 0 and 1 in the first treemap, 1 and 2 in the second
+    let treemap1: Treemap = (0..300).chain((1 << 32)..(1 << 32) + 100).collect();
+    let treemap2: Treemap = ((1 << 32) + 50..(1 << 32) + 150).chain((2 << 32)..(2 << 32) + 250).collect();
+
+    // 50 shared values out of 300 + 100 + 100 + 250 - 50
+    assert_eq!(treemap1.and_cardinality(&treemap2), 50);
+    assert_eq!(treemap1.jaccard_index(&treemap2), 50.0 / 700.0);
+    assert_eq!(treemap2.jaccard_index(&treemap1), treemap1.jaccard_index(&treemap2));
+
+    assert_eq!(treemap1.jaccard_index(&treemap1.clone()), 1.0);
+    assert_eq!(treemap1.jaccard_index(&Treemap::of(&[3 << 32])), 0.0);
+    assert!(Treemap::create().jaccard_index(&Treemap::create()).is_nan());
+}
+
+#[test]
+fn treemap_predicates_partially_shared_keys() {
+    // High keys 0 and 1 in the smaller treemap, 0, 1 and 2 in the larger one
+    let small: Treemap = (10..20).chain((1 << 32) + 5..(1 << 32) + 10).collect();
+    let large: Treemap = (0..100).chain((1 << 32)..(1 << 32) + 50).chain([2 << 32]).collect();
+    let disjoint: Treemap = (20..30).chain([2 << 32, 3 << 32]).collect();
+
+    assert!(small.is_subset(&large));
+    assert!(!large.is_subset(&small));
+    assert!(small.is_subset(&small));
+    assert!(!disjoint.is_subset(&large));
+    assert!(Treemap::create().is_subset(&small));
+
+    assert!(small.intersect(&large));
+    assert!(large.intersect(&disjoint));
+    assert!(!small.intersect(&disjoint));
+    assert!(!small.intersect(&Treemap::create()));
+
+    assert!(large.contains(2 << 32));
+    assert!(!small.contains(2 << 32));
+    assert!(small.contains((1 << 32) + 5));
+    assert!(!small.contains(1 << 32));
+
+    // A high key left with an empty bitmap does not make the treemap non-empty
+    let mut with_empty_key = small.clone();
+    with_empty_key.map.insert(7, Bitmap::create());
+    assert!(with_empty_key.is_subset(&large));
+    assert!(!with_empty_key.is_empty());
+    let mut emptied = Treemap::create();
+    emptied.map.insert(7, Bitmap::create());
+    assert!(emptied.is_empty());
+    assert!(emptied.is_subset(&Treemap::create()));
+}
+
+#[test]
+fn treemap_flip_across_high_keys() {
+    let original = Treemap::of(&[0, (1 << 32) - 1, 1 << 32, (3 << 32) + 7, u64::MAX]);
+    let range = (1 << 32) - 3..(3 << 32) + 10;
+
+    let flipped = original.flip(range.clone());
+    assert!(!flipped.contains((1 << 32) - 1));
+    assert!(flipped.contains((1 << 32) - 2));
+    assert!(!flipped.contains(1 << 32));
+    assert!(flipped.contains((1 << 32) + 1));
+    assert!(!flipped.contains((3 << 32) + 7));
+    assert!(flipped.contains((3 << 32) + 9));
+    assert!(!flipped.contains((3 << 32) + 10));
+    assert!(flipped.contains(0));
+    assert!(flipped.contains(u64::MAX));
+
+    // High key 2 had no values and is entirely inside the range
+    assert_eq!(flipped.map[&2].cardinality(), 1 << 32);
+    assert_eq!(
+        flipped.cardinality(),
+        original.cardinality() + (range.end - range.start) - 2 * 3
+    );
+
+    assert_eq!(flipped.flip(range.clone()), original);
+
+    let mut inplace = original.clone();
+    inplace.flip_inplace(range.clone());
+    assert_eq!(inplace, flipped);
+    inplace.flip_inplace(range);
+    assert_eq!(inplace, original);
+    // High keys emptied by the second flip are pruned
+    assert_eq!(inplace.map.keys().copied().collect::<Vec<u32>>(), [0, 1, 3, u32::MAX]);
+
+    let mut edge = Treemap::of(&[u64::MAX]);
+    edge.flip_inplace(u64::MAX - 1..=u64::MAX);
+    assert_eq!(edge.to_vec(), [u64::MAX - 1]);
+    edge.flip_inplace(5..5);
+    assert_eq!(edge.to_vec(), [u64::MAX - 1]);
+}
+
+#[test]
+fn treemap_bitmap_conversions_roundtrip() {
+    let bitmap: Bitmap = (0..100).chain([70_000, u32::MAX]).collect();
+
+    let treemap = Treemap::from(bitmap.clone());
+    assert_eq!(treemap.cardinality(), bitmap.cardinality());
+    assert!(treemap.iter().eq(bitmap.iter().map(u64::from)));
+    assert_eq!(treemap.into_bitmap(), Some(bitmap.clone()));
+
+    // Install the same bitmap as several shards
+    let mut sharded = Treemap::create();
+    for shard in [0, 5, u32::MAX] {
+        sharded.add_bitmap_at(shard, bitmap.clone());
+    }
+    assert_eq!(sharded.cardinality(), 3 * bitmap.cardinality());
+    assert!(sharded.contains((5 << 32) + 70_000));
+    assert_eq!(sharded.maximum(), Some(u64::MAX));
+    for shard in [0, 5, u32::MAX] {
+        assert_eq!(sharded.map[&shard], bitmap);
+    }
+    assert_eq!(sharded.clone().into_bitmap(), None);
+
+    // Adding at an existing shard merges the bitmaps
+    sharded.add_bitmap_at(5, Bitmap::of(&[100, 200]));
+    assert_eq!(sharded.map[&5].cardinality(), bitmap.cardinality() + 2);
+
+    // Emptying the other shards makes the treemap convertible again
+    sharded.remove_range(1 << 32..);
+    assert_eq!(sharded.into_bitmap(), Some(bitmap.clone()));
+
+    let mut empty_shards = Treemap::create();
+    empty_shards.add_bitmap_at(3, Bitmap::create());
+    assert!(empty_shards.map.is_empty());
+    empty_shards.map.insert(4, Bitmap::create());
+    assert_eq!(empty_shards.into_bitmap(), Some(Bitmap::create()));
+}
+
+#[test]
+fn treemap_cardinality_of_full_inner_bitmaps() {
+    let full = Bitmap::from_range(..);
+    assert!(full.is_full());
+
+    let mut treemap = Treemap::create();
+    for high in [0, 1, 7, u32::MAX] {
+        treemap.add_bitmap_at(high, full.clone());
+    }
+    assert_eq!(treemap.cardinality(), 4 << 32);
+    assert_eq!(treemap.statistics().cardinality, 4 << 32);
+    assert!(!treemap.is_full());
+
+    treemap.add_range(..(8 << 32));
+    assert_eq!(treemap.cardinality(), 9 << 32);
+    assert!(!treemap.is_full());
+
+    treemap.remove(u64::MAX);
+    assert_eq!(treemap.cardinality(), (9 << 32) - 1);
+    assert_eq!(treemap.maximum(), Some(u64::MAX - 1));
+
+    // A full treemap has 2^64 values, which saturates the count. Building one
+    // needs every one of the 2^32 inner bitmaps, so only the empty case is checked.
+    assert!(!Treemap::create().is_full());
+    assert_eq!(Treemap::create().cardinality(), 0);
+}
+
+#[test]
+fn treemap_operation_cardinalities_of_full_inner_bitmaps() {
+    // Full inner bitmaps under high keys 0..4 and 2..6, plus a few values under key 9
+    let mut treemap1 = Treemap::create();
+    treemap1.add_range(..(4 << 32));
+    let mut treemap2 = Treemap::create();
+    treemap2.add_range((2 << 32)..(6 << 32));
+    treemap2.add_range((9 << 32)..(9 << 32) + 10);
+
+    assert_eq!(treemap1.and_cardinality(&treemap2), 2 << 32);
+    assert_eq!(treemap1.or_cardinality(&treemap2), (6 << 32) + 10);
+    assert_eq!(treemap1.xor_cardinality(&treemap2), (4 << 32) + 10);
+    assert_eq!(treemap1.andnot_cardinality(&treemap2), 2 << 32);
+    assert_eq!(treemap2.andnot_cardinality(&treemap1), (2 << 32) + 10);
+    assert_eq!(treemap1.jaccard_index(&treemap2), (2u64 << 32) as f64 / ((6u64 << 32) + 10) as f64);
+
+    for (a, b) in [(&treemap1, &treemap2), (&treemap2, &treemap1), (&treemap1, &treemap1)] {
+        assert_eq!(a.and_cardinality(b), a.and(b).cardinality());
+        assert_eq!(a.or_cardinality(b), a.or(b).cardinality());
+        assert_eq!(a.xor_cardinality(b), a.xor(b).cardinality());
+        assert_eq!(a.andnot_cardinality(b), a.andnot(b).cardinality());
+    }
+
+    // Sums of the full inner bitmaps saturate rather than overflow. Reaching
+    // u64::MAX takes all 2^32 high keys, so only the unsaturated sums are checked.
+    assert_eq!(treemap1.or_cardinality(&Treemap::create()), 4 << 32);
+    assert_eq!(Treemap::create().xor_cardinality(&treemap1), 4 << 32);
+}
+
+proptest! {
+    #[test]
+    fn bitmap_cardinality_roundtrip(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 1..3000)
+    ) {
+        let original = Bitmap::of(&indices);
+        let mut a = indices.clone();
+        a.sort();
+        a.dedup();
+        prop_assert_eq!(a.len(), original.cardinality() as usize);
+    }
+
+    #[test]
+    fn bitmap_from_sorted_slice(
+        indices in prop::collection::btree_set(proptest::num::u32::ANY, 2..3000),
+        swap in proptest::num::usize::ANY
+    ) {
+        let sorted: Vec<u32> = indices.into_iter().collect();
+        let bitmap = Bitmap::from_sorted_slice(&sorted);
+        prop_assert_eq!(bitmap, Some(Bitmap::of(&sorted)));
+
+        let i = swap % (sorted.len() - 1);
+
+        let mut unsorted = sorted.clone();
+        unsorted.swap(i, i + 1);
+        prop_assert!(Bitmap::from_sorted_slice(&unsorted).is_none());
+
+        let mut duplicated = sorted.clone();
+        duplicated.insert(i, sorted[i]);
+        prop_assert!(Bitmap::from_sorted_slice(&duplicated).is_none());
+    }
+
+    #[test]
+    fn bitmap_std_set_roundtrip(
+        set in prop::collection::btree_set(proptest::num::u32::ANY, 0..3000)
+    ) {
+        let bitmap = Bitmap::from(&set);
+        prop_assert_eq!(bitmap.cardinality(), set.len() as u64);
+        prop_assert_eq!(BTreeSet::from(&bitmap), set.clone());
+
+        let hash_set: std::collections::HashSet<u32> = set.iter().copied().collect();
+        prop_assert_eq!(Bitmap::from(&hash_set), bitmap);
+    }
+
+    #[test]
+    fn bitmap_fast_or_matches_fold(
+        bitmaps in prop::collection::vec(prop::collection::vec(proptest::num::u32::ANY, 0..200), 0..20)
+    ) {
+        let bitmaps: Vec<Bitmap> = bitmaps.iter().map(|indices| Bitmap::of(indices)).collect();
+        let refs: Vec<&Bitmap> = bitmaps.iter().collect();
+
+        let expected = bitmaps.iter().fold(Bitmap::create(), |acc, bitmap| acc | bitmap);
+
+        prop_assert_eq!(&Bitmap::fast_or(&refs), &expected);
+        prop_assert_eq!(Bitmap::fast_or_heap(&refs), expected);
+    }
+
+    #[test]
+    fn bitmap_fast_xor_parity(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..500),
+        others in prop::collection::vec(prop::collection::vec(proptest::num::u32::ANY, 0..200), 0..5),
+        copies in 0usize..6
+    ) {
+        let bitmap = Bitmap::of(&indices);
+        let others: Vec<Bitmap> = others.iter().map(|indices| Bitmap::of(indices)).collect();
+
+        let mut refs: Vec<&Bitmap> = others.iter().collect();
+        refs.extend(std::iter::repeat(&bitmap).take(copies));
 
-            for i in 100..1000 {
-                assert!(treemap.contains(i));
+        let others_xor = others.iter().fold(Bitmap::create(), |acc, other| acc ^ other);
+        let expected = if copies % 2 == 0 { others_xor } else { others_xor ^ &bitmap };
+
+        prop_assert_eq!(&Bitmap::fast_xor(&refs), &expected);
+        prop_assert_eq!(Bitmap::fast_xor(&refs), refs.iter().fold(Bitmap::create(), |acc, &other| acc ^ other));
+    }
+
+    #[test]
+    fn bitmap_owned_inplace_ops_match_borrowed(
+        indices1 in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        indices2 in prop::collection::vec(proptest::num::u32::ANY, 0..300),
+        copy_on_write in proptest::bool::ANY
+    ) {
+        let a = Bitmap::of(&indices1);
+        let mut b = Bitmap::of(&indices2);
+        unsafe { b.set_copy_on_write(copy_on_write) };
+
+        for (x, y) in [(&a, &b), (&b, &a)] {
+            let mut result = x.clone();
+            let copy_on_write = result.get_copy_on_write();
+            result.and_inplace_owned(y.clone());
+            prop_assert_eq!(&result, &x.and(y));
+            prop_assert_eq!(result.get_copy_on_write(), copy_on_write);
+
+            let mut result = x.clone();
+            let copy_on_write = result.get_copy_on_write();
+            result.or_inplace_owned(y.clone());
+            prop_assert_eq!(&result, &x.or(y));
+            prop_assert_eq!(result.get_copy_on_write(), copy_on_write);
+
+            let mut result = x.clone();
+            let copy_on_write = result.get_copy_on_write();
+            result.xor_inplace_owned(y.clone());
+            prop_assert_eq!(&result, &x.xor(y));
+            prop_assert_eq!(result.get_copy_on_write(), copy_on_write);
+
+            let mut result = x.clone();
+            result |= y.clone();
+            result ^= y.clone();
+            result &= x.clone();
+            prop_assert_eq!(&result, &x.andnot(y));
+        }
+    }
+
+    #[test]
+    fn bitmap_operation_cardinalities(
+        indices1 in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        indices2 in prop::collection::vec(proptest::num::u32::ANY, 0..3000)
+    ) {
+        let a = Bitmap::of(&indices1);
+        let b = Bitmap::of(&indices2);
+
+        prop_assert_eq!(a.and_cardinality(&b), a.and(&b).cardinality());
+        prop_assert_eq!(a.or_cardinality(&b), a.or(&b).cardinality());
+        prop_assert_eq!(a.andnot_cardinality(&b), a.andnot(&b).cardinality());
+        prop_assert_eq!(a.xor_cardinality(&b), a.xor(&b).cardinality());
+    }
+
+    #[test]
+    fn bitmap_range_cardinality(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        start in proptest::num::u32::ANY,
+        end in proptest::num::u32::ANY
+    ) {
+        let bitmap = Bitmap::of(&indices);
+
+        let expected = bitmap.iter().filter(|&value| value >= start && value < end).count();
+        prop_assert_eq!(bitmap.range_cardinality(start..end), expected as u64);
+
+        let expected = bitmap.iter().filter(|&value| value >= start && value <= end).count();
+        prop_assert_eq!(bitmap.range_cardinality(start..=end), expected as u64);
+    }
+
+    #[test]
+    fn bitmap_intersect_with_range_inplace(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        ranges in prop::collection::vec((proptest::num::u32::ANY, 0u32..200_000), 0..5),
+        lo in proptest::num::u32::ANY,
+        hi in proptest::num::u32::ANY
+    ) {
+        let mut bitmap = Bitmap::of(&indices);
+        for (start, len) in ranges {
+            bitmap.add_range(start..start.saturating_add(len));
+        }
+
+        let mut result = bitmap.clone();
+        result.intersect_with_range_inplace(lo..hi);
+        let mut expected = bitmap.clone();
+        expected &= &Bitmap::from_range(lo..hi);
+        prop_assert_eq!(result, expected);
+
+        let mut result = bitmap.clone();
+        result.intersect_with_range_inplace(lo..=hi);
+        prop_assert_eq!(result, &bitmap & &Bitmap::from_range(lo..=hi));
+
+        let mut result = bitmap.clone();
+        result.intersect_with_range_inplace(lo..);
+        prop_assert_eq!(result, &bitmap & &Bitmap::from_range(lo..));
+
+        let mut result = bitmap.clone();
+        result.intersect_with_range_inplace(..);
+        prop_assert_eq!(result, bitmap);
+    }
+
+    #[test]
+    fn bitmap_get_index(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        probes in prop::collection::vec(proptest::num::u32::ANY, 0..20)
+    ) {
+        let bitmap = Bitmap::of(&indices);
+        let sorted = bitmap.to_vec();
+
+        for (i, &value) in sorted.iter().enumerate() {
+            prop_assert_eq!(bitmap.get_index(value), Some(i as u64));
+        }
+        if let Some(minimum) = bitmap.minimum() {
+            prop_assert_eq!(bitmap.get_index(minimum), Some(0));
+        }
+        for probe in probes {
+            let expected = sorted.binary_search(&probe).ok().map(|i| i as u64);
+            prop_assert_eq!(bitmap.get_index(probe), expected);
+        }
+    }
+
+    #[test]
+    fn bitmap_from_range_with_step(
+        start in proptest::num::u32::ANY,
+        len in 0u32..100_000,
+        step in 1u32..1000
+    ) {
+        let end = start.saturating_add(len);
+        let expected: Vec<u32> = (start..end).step_by(step as usize).collect();
+        prop_assert_eq!(Bitmap::from_range_with_step(start..end, step), expected);
+
+        let expected: Vec<u32> = (start..=end).step_by(step as usize).collect();
+        prop_assert_eq!(Bitmap::from_range_with_step(start..=end, step), expected);
+
+        prop_assert_eq!(Bitmap::from_range_with_step(start..end, 1), Bitmap::from_range(start..end));
+        prop_assert!(Bitmap::from_range_with_step(start..end, 0).is_empty());
+    }
+
+    #[test]
+    fn bitmap_add_offset(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        offset in -(1i64 << 33)..(1i64 << 33)
+    ) {
+        let bitmap = Bitmap::of(&indices);
+
+        let expected: Vec<u32> = bitmap
+            .iter()
+            .filter_map(|value| u32::try_from(i64::from(value) + offset).ok())
+            .collect();
+        prop_assert_eq!(bitmap.add_offset(offset).to_vec(), expected);
+    }
+
+    #[test]
+    fn bitmap_ordering_matches_sorted_vecs(
+        indices1 in prop::collection::vec(0u32..100, 0..10),
+        indices2 in prop::collection::vec(0u32..100, 0..10)
+    ) {
+        let a = Bitmap::of(&indices1);
+        let b = Bitmap::of(&indices2);
+
+        prop_assert_eq!(a.cmp(&b), a.to_vec().cmp(&b.to_vec()));
+        prop_assert_eq!(a.cmp(&b) == std::cmp::Ordering::Equal, a == b);
+        prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+    }
+
+    #[test]
+    fn bitmap_iterator_len_mixed_iteration(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        steps in prop::collection::vec(0u8..3, 0..100)
+    ) {
+        let bitmap = Bitmap::of(&indices);
+        let mut expected: VecDeque<u32> = bitmap.to_vec().into();
+        let mut iter = bitmap.iter();
+        let mut buf = [0; 50];
+
+        for step in steps {
+            match step {
+                0 => prop_assert_eq!(iter.next(), expected.pop_front()),
+                1 => prop_assert_eq!(iter.next_back(), expected.pop_back()),
+                _ => {
+                    let n = iter.next_many(&mut buf);
+                    let n_expected = buf.len().min(expected.len());
+                    let expected_values: Vec<u32> = expected.drain(..n_expected).collect();
+                    prop_assert_eq!(&buf[..n], &expected_values[..]);
+                }
             }
+            prop_assert_eq!(iter.len(), expected.len());
+        }
+        prop_assert!(iter.eq(expected));
+    }
 
-            assert!(treemap.contains(std::u32::MAX as u64));
-            assert!(treemap.contains(std::u64::MAX));
+    #[test]
+    fn bitmap_iterator_advance_to(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        targets in prop::collection::vec(proptest::num::u32::ANY, 0..10),
+        from_back in 0usize..10
+    ) {
+        let bitmap = Bitmap::of(&indices);
+        let mut expected = bitmap.to_vec();
+        let mut iter = bitmap.iter();
+        for _ in 0..from_back {
+            prop_assert_eq!(iter.next_back(), expected.pop());
         }
-        Err(err) => assert!(false, "Cannot read test file {}", err),
+
+        let mut start = 0;
+        for target in targets {
+            start += expected[start..].iter().take_while(|&&value| value < target).count();
+            prop_assert_eq!(iter.advance_to(target), expected.get(start).copied());
+            prop_assert_eq!(iter.len(), expected.len() - start);
+        }
+        prop_assert!(iter.eq(expected[start..].iter().copied()));
     }
-}
 
-proptest! {
     #[test]
-    fn bitmap_cardinality_roundtrip(
-        indices in prop::collection::vec(proptest::num::u32::ANY, 1..3000)
+    fn bitmap_iterator_last_nth(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        steps in prop::collection::vec(0usize..1000, 0..10),
+        from_front in 0usize..10,
+        from_back in 0usize..10
     ) {
-        let original = Bitmap::of(&indices);
-        let mut a = indices.clone();
-        a.sort();
-        a.dedup();
-        prop_assert_eq!(a.len(), original.cardinality() as usize);
+        let bitmap = Bitmap::of(&indices);
+        prop_assert_eq!(bitmap.iter().last(), bitmap.maximum());
+
+        let mut iter = bitmap.iter();
+        let mut expected = bitmap.to_vec().into_iter();
+        for _ in 0..from_front {
+            prop_assert_eq!(iter.next(), expected.next());
+        }
+        for _ in 0..from_back {
+            prop_assert_eq!(iter.next_back(), expected.next_back());
+        }
+        prop_assert_eq!(iter.clone().last(), expected.clone().last());
+
+        for n in steps {
+            prop_assert_eq!(iter.nth(n), expected.nth(n));
+            prop_assert_eq!(iter.len(), expected.len());
+        }
+        prop_assert!(iter.eq(expected));
+    }
+
+    #[test]
+    fn bitmap_iterator_peek(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        from_front in prop::collection::vec(proptest::bool::ANY, 0..3100),
+        range in (proptest::num::u32::ANY, proptest::num::u32::ANY)
+    ) {
+        let bitmap = Bitmap::of(&indices);
+        let mut iter = bitmap.iter();
+        for front in from_front {
+            if front {
+                let peeked = iter.peek();
+                prop_assert_eq!(iter.next(), peeked);
+            } else {
+                let peeked = iter.peek_back();
+                prop_assert_eq!(iter.next_back(), peeked);
+            }
+            prop_assert_eq!(iter.peek().is_none(), iter.len() == 0);
+            prop_assert_eq!(iter.peek_back().is_none(), iter.len() == 0);
+        }
+
+        let (start, end) = (range.0.min(range.1), range.0.max(range.1));
+        let mut iter = bitmap.iter_range(start..end);
+        prop_assert_eq!(iter.peek_back(), iter.clone().next_back());
+        while let Some(peeked) = iter.peek() {
+            prop_assert_eq!(iter.next(), Some(peeked));
+        }
+        prop_assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn bitmap_iterator_collect_into(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        front in 0usize..100,
+        back in 0usize..100
+    ) {
+        let bitmap = Bitmap::of(&indices);
+        let mut iter = bitmap.iter();
+        iter.by_ref().take(front).for_each(drop);
+        iter.by_ref().rev().take(back).for_each(drop);
+
+        let expected: Vec<u32> = iter.clone().collect();
+        let mut collected = vec![7];
+        iter.collect_into(&mut collected);
+        prop_assert_eq!(collected[0], 7);
+        prop_assert_eq!(&collected[1..], &expected[..]);
+        prop_assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn bitmap_extend_matches_add(
+        initial in prop::collection::vec(proptest::num::u32::ANY, 0..100),
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        sorted_prefix in 0usize..3000
+    ) {
+        let mut expected = Bitmap::of(&initial);
+        for &i in &indices {
+            expected.add(i);
+        }
+
+        let mut extended = Bitmap::of(&initial);
+        extended.extend(indices.iter().copied());
+        prop_assert_eq!(&extended, &expected);
+
+        // Sorted input, and input which is only partly sorted
+        let mut values = indices.clone();
+        values.sort_unstable();
+        let mut extended = Bitmap::of(&initial);
+        extended.extend_sorted(values.iter().copied());
+        prop_assert_eq!(&extended, &expected);
+
+        values[..sorted_prefix.min(indices.len())].reverse();
+        let mut extended = Bitmap::of(&initial);
+        extended.extend_sorted(values);
+        prop_assert_eq!(&extended, &expected);
+    }
+
+    #[test]
+    fn bitmap_dense_bitset_roundtrip(
+        indices in prop::collection::vec(0u32..1_000_000, 0..3000)
+    ) {
+        let bitmap = Bitmap::of(&indices);
+        let words = bitmap.to_dense_bitset();
+
+        let expected_len = bitmap.maximum().map_or(0, |maximum| maximum as usize / 64 + 1);
+        prop_assert_eq!(words.len(), expected_len);
+        prop_assert_eq!(
+            words.iter().map(|word| u64::from(word.count_ones())).sum::<u64>(),
+            bitmap.cardinality()
+        );
+        for &i in &indices {
+            prop_assert!(words[i as usize / 64] & (1 << (i % 64)) != 0);
+        }
+        prop_assert_eq!(Bitmap::from_dense_bitset(&words), bitmap);
+    }
+
+    #[test]
+    fn bitmap_iter_runs(
+        indices in prop::collection::vec(0u32..5000, 0..3000),
+        ranges in prop::collection::vec((proptest::num::u32::ANY, 0u32..100_000), 0..10)
+    ) {
+        let mut bitmap = Bitmap::of(&indices);
+        for (start, len) in ranges {
+            bitmap.add_range(start..start.saturating_add(len));
+        }
+
+        let mut expected: Vec<(u32, u32)> = Vec::new();
+        for value in bitmap.iter() {
+            match expected.last_mut() {
+                Some((start, len)) if start.checked_add(*len) == Some(value) => *len += 1,
+                _ => expected.push((value, 1)),
+            }
+        }
+
+        bitmap.remove_run_compression();
+        prop_assert_eq!(bitmap.iter_runs().collect::<Vec<_>>(), expected.clone());
+        bitmap.run_optimize();
+        prop_assert_eq!(bitmap.iter_runs().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn bitmap_containers_split_losslessly(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        ranges in prop::collection::vec((proptest::num::u32::ANY, 0u32..200_000), 0..5)
+    ) {
+        let mut bitmap = Bitmap::of(&indices);
+        for (start, len) in ranges {
+            bitmap.add_range(start..start.saturating_add(len));
+        }
+
+        let containers: Vec<(u16, Bitmap)> = bitmap.containers().collect();
+
+        let mut expected_keys: Vec<u16> = bitmap.iter().map(|value| (value >> 16) as u16).collect();
+        expected_keys.dedup();
+        let keys: Vec<u16> = containers.iter().map(|&(key, _)| key).collect();
+        prop_assert_eq!(keys, expected_keys);
+
+        for (key, piece) in &containers {
+            prop_assert!(!piece.is_empty());
+            prop_assert!(piece.iter().all(|value| (value >> 16) as u16 == *key));
+        }
+
+        let pieces: Vec<&Bitmap> = containers.iter().map(|(_, piece)| piece).collect();
+        prop_assert_eq!(Bitmap::fast_or(&pieces), bitmap);
+    }
+
+    #[test]
+    fn bitmap_merge_iter_matches_fast_or(
+        bitmaps in prop::collection::vec(
+            prop::collection::vec(0u32..100_000, 0..500)
+                .prop_map(|values| Bitmap::of(&values)),
+            0..10,
+        )
+    ) {
+        let refs: Vec<&Bitmap> = bitmaps.iter().collect();
+        let merged: Vec<u32> = Bitmap::merge_iter(&refs).collect();
+        let expected: Vec<u32> = Bitmap::fast_or(&refs).iter().collect();
+        prop_assert_eq!(merged, expected);
+
+        // The same bitmap repeated is merged into one copy
+        let repeated: Vec<&Bitmap> = refs.iter().chain(refs.iter()).copied().collect();
+        prop_assert!(Bitmap::merge_iter(&repeated).eq(Bitmap::merge_iter(&refs)));
+    }
+
+    #[test]
+    fn bitmap_intersect_iter_matches_and(
+        bitmaps in prop::collection::vec(
+            (prop::collection::vec(0u32..20_000, 0..3000), 0u32..10, 0u32..30_000)
+                .prop_map(|(values, range_start, range_len)| {
+                    // Shared runs make non-empty intersections likely
+                    let mut bitmap = Bitmap::of(&values);
+                    bitmap.add_range(range_start * 1_000..range_start * 1_000 + range_len);
+                    bitmap
+                }),
+            1..6,
+        )
+    ) {
+        let refs: Vec<&Bitmap> = bitmaps.iter().collect();
+        let common: Vec<u32> = Bitmap::intersect_iter(&refs).collect();
+
+        let mut expected = bitmaps[0].clone();
+        for bitmap in &bitmaps[1..] {
+            expected.and_inplace(bitmap);
+        }
+        prop_assert_eq!(common, expected.to_vec());
+
+        let mut iter = Bitmap::intersect_iter(&refs);
+        iter.by_ref().for_each(drop);
+        prop_assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn bitmap_iter_range(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        start in proptest::num::u32::ANY,
+        end in proptest::num::u32::ANY
+    ) {
+        let bitmap = Bitmap::of(&indices);
+
+        let expected: Vec<u32> = bitmap.iter().filter(|&value| value >= start && value < end).collect();
+        let mut iter = bitmap.iter_range(start..end);
+        prop_assert_eq!(iter.len(), expected.len());
+        prop_assert_eq!(iter.clone().collect::<Vec<_>>(), expected.clone());
+        prop_assert_eq!(iter.clone().rev().collect::<Vec<_>>(), expected.iter().rev().copied().collect::<Vec<_>>());
+
+        let mut buf = vec![0; expected.len() + 1];
+        prop_assert_eq!(iter.next_many(&mut buf), expected.len());
+        prop_assert_eq!(&buf[..expected.len()], &expected[..]);
     }
 
     #[test]
@@ -161,19 +1836,352 @@ proptest! {
         prop_assert_eq!(a.len(), original.cardinality() as usize);
     }
 
+    #[test]
+    fn treemap_iter_both_ends(
+        indices in prop::collection::vec(proptest::num::u64::ANY, 0..3000),
+        front in proptest::bool::ANY
+    ) {
+        let treemap = Treemap::of(&indices);
+        let mut expected = indices.clone();
+        expected.sort_unstable();
+        expected.dedup();
+
+        prop_assert_eq!(treemap.iter().collect::<Vec<_>>(), expected.clone());
+        prop_assert_eq!(treemap.iter().rev().collect::<Vec<_>>(), expected.iter().rev().copied().collect::<Vec<_>>());
+
+        let mut iter = treemap.iter();
+        let mut model: VecDeque<u64> = expected.into();
+        loop {
+            let (actual, wanted) = if front || model.len() % 3 == 0 {
+                (iter.next(), model.pop_front())
+            } else {
+                (iter.next_back(), model.pop_back())
+            };
+            prop_assert_eq!(actual, wanted);
+            if actual.is_none() {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn treemap_collect_matches_add(
+        indices in prop::collection::vec(
+            prop_oneof![
+                proptest::num::u64::ANY,
+                (u64::from(u32::MAX) - 100)..(u64::from(u32::MAX) + 100),
+            ],
+            0..3000
+        )
+    ) {
+        let mut added = Treemap::create();
+        for &index in &indices {
+            added.add(index);
+        }
+
+        let collected: Treemap = indices.iter().copied().collect();
+        prop_assert_eq!(&collected, &added);
+
+        let mut extended = Treemap::create();
+        extended.extend(indices.iter().copied());
+        prop_assert_eq!(&extended, &added);
+
+        let expected: Vec<u64> = added.iter().collect();
+        prop_assert_eq!((&added).into_iter().collect::<Vec<_>>(), expected.clone());
+        prop_assert_eq!(added.clone().into_iter().rev().collect::<Vec<_>>(), expected.iter().rev().copied().collect::<Vec<_>>());
+        prop_assert_eq!(added.into_iter().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn treemap_iter_next_many(
+        indices in prop::collection::vec(
+            prop_oneof![
+                proptest::num::u64::ANY,
+                0u64..(4 << 32),
+            ],
+            0..3000
+        ),
+        chunk_size in 1usize..1000,
+        from_back in 0usize..100
+    ) {
+        let treemap = Treemap::of(&indices);
+        let expected: Vec<u64> = treemap.iter().collect();
+        let from_back = from_back.min(expected.len());
+
+        let mut iter = treemap.iter();
+        let mut back = Vec::new();
+        for _ in 0..from_back {
+            back.extend(iter.next_back());
+        }
+
+        let mut actual = Vec::new();
+        let mut buf = vec![0; chunk_size];
+        loop {
+            let n = iter.next_many(&mut buf);
+            actual.extend_from_slice(&buf[..n]);
+            if n < chunk_size {
+                break;
+            }
+        }
+        prop_assert_eq!(iter.next_many(&mut buf), 0);
+        prop_assert_eq!(iter.next_back(), None);
+
+        back.reverse();
+        actual.extend(back);
+        prop_assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn treemap_operators_match_sets(
+        a in prop::collection::vec((0u32..6, proptest::num::u32::ANY), 0..500),
+        b in prop::collection::vec((3u32..9, proptest::num::u32::ANY), 0..500)
+    ) {
+        let join = |(high, low): (u32, u32)| (u64::from(high) << 32) | u64::from(low);
+        let a: BTreeSet<u64> = a.into_iter().map(join).collect();
+        let b: BTreeSet<u64> = b.into_iter().map(join).collect();
+        let treemap_a: Treemap = a.iter().copied().collect();
+        let mut treemap_b: Treemap = b.iter().copied().collect();
+
+        // A cleared high key leaves an empty bitmap behind, which must not be copied
+        treemap_b.add(20 << 32);
+        treemap_b.map.get_mut(&20).unwrap().clear();
+
+        let check = |treemap: &Treemap, expected: Vec<u64>| {
+            assert!(treemap.map.values().all(|bitmap| !bitmap.is_empty()));
+            assert_eq!(treemap, &expected.iter().copied().collect::<Treemap>());
+            assert_eq!(treemap.to_vec(), expected);
+        };
+
+        let and: Vec<u64> = a.intersection(&b).copied().collect();
+        let or: Vec<u64> = a.union(&b).copied().collect();
+        let xor: Vec<u64> = a.symmetric_difference(&b).copied().collect();
+        let andnot: Vec<u64> = a.difference(&b).copied().collect();
+
+        check(&(&treemap_a & &treemap_b), and.clone());
+        check(&(&treemap_a | &treemap_b), or.clone());
+        check(&(&treemap_a ^ &treemap_b), xor.clone());
+        check(&(&treemap_a - &treemap_b), andnot.clone());
+
+        let mut result = treemap_a.clone();
+        result &= &treemap_b;
+        check(&result, and);
+
+        let mut result = treemap_a.clone();
+        result |= &treemap_b;
+        check(&result, or);
+
+        let mut result = treemap_a.clone();
+        result ^= &treemap_b;
+        check(&result, xor);
+
+        let mut result = treemap_a.clone();
+        result -= &treemap_b;
+        check(&result, andnot);
+    }
+
+    #[test]
+    fn treemap_operation_cardinalities(
+        a in prop::collection::vec((0u32..6, proptest::num::u32::ANY), 0..500),
+        b in prop::collection::vec((3u32..9, proptest::num::u32::ANY), 0..500)
+    ) {
+        let join = |(high, low): (u32, u32)| (u64::from(high) << 32) | u64::from(low);
+        let a: Treemap = a.into_iter().map(join).collect();
+        let b: Treemap = b.into_iter().map(join).collect();
+
+        // Only high keys 3, 4 and 5 can be shared
+        for (a, b) in [(&a, &b), (&b, &a)] {
+            prop_assert_eq!(a.and_cardinality(b), a.and(b).cardinality());
+            prop_assert_eq!(a.or_cardinality(b), a.or(b).cardinality());
+            prop_assert_eq!(a.andnot_cardinality(b), a.andnot(b).cardinality());
+            prop_assert_eq!(a.xor_cardinality(b), a.xor(b).cardinality());
+        }
+    }
+
+    #[test]
+    fn treemap_rank_select(
+        indices in prop::collection::vec((0u32..4, proptest::num::u32::ANY), 1..1000),
+        probes in prop::collection::vec(0u64..(5 << 32), 0..20)
+    ) {
+        let treemap: Treemap = indices
+            .into_iter()
+            .map(|(high, low)| (u64::from(high) << 32) | u64::from(low))
+            .collect();
+        let values = treemap.to_vec();
+
+        for (rank, &value) in values.iter().enumerate() {
+            prop_assert_eq!(treemap.select(rank as u64), Some(value));
+            prop_assert_eq!(treemap.rank(value), rank as u64 + 1);
+        }
+        prop_assert_eq!(treemap.select(values.len() as u64), None);
+
+        for probe in probes {
+            let expected = values.iter().filter(|&&value| value <= probe).count();
+            prop_assert_eq!(treemap.rank(probe), expected as u64);
+        }
+    }
+
+    #[test]
+    fn treemap_minimum_maximum(
+        indices in prop::collection::vec((0u32..4, proptest::num::u32::ANY), 0..1000),
+        single_key in proptest::bool::ANY
+    ) {
+        let treemap: Treemap = indices
+            .into_iter()
+            .map(|(high, low)| if single_key { (0, low) } else { (high, low) })
+            .map(|(high, low)| (u64::from(high) << 32) | u64::from(low))
+            .collect();
+
+        prop_assert_eq!(treemap.minimum(), treemap.iter().next());
+        prop_assert_eq!(treemap.maximum(), treemap.iter().next_back());
+    }
+
+    #[test]
+    fn treemap_range_ops_match_elementwise(
+        start in (1u64 << 32) - 1000..(1u64 << 32) + 1000,
+        len in 0u64..5000,
+        offset in prop_oneof![Just(0u64), Just(1u64 << 32), Just(u64::MAX - (1 << 32) - 20000)],
+        remove_start in 0u64..6000,
+        remove_len in 0u64..6000
+    ) {
+        let start = start.wrapping_add(offset);
+        let remove_start = start - 500 + remove_start;
+
+        let mut ranged = Treemap::create();
+        ranged.add_range(start..start + len);
+        let mut expected = Treemap::create();
+        for value in start..start + len {
+            expected.add(value);
+        }
+        prop_assert_eq!(&ranged, &expected);
+
+        ranged.remove_range(remove_start..=remove_start + remove_len);
+        for value in remove_start..=remove_start + remove_len {
+            expected.remove(value);
+        }
+        prop_assert_eq!(ranged.to_vec(), expected.to_vec());
+        prop_assert!(ranged.map.values().all(|bitmap| !bitmap.is_empty()));
+    }
+
     #[test]
     fn test_bitmap_serialization_roundtrip(
         indices in prop::collection::vec(proptest::num::u32::ANY, 1..3000)
     ) {
         let original = Bitmap::of(&indices);
 
-        let buffer = original.serialize();
+        let buffer = original.serialize::<Portable>();
 
-        let deserialized = Bitmap::deserialize(&buffer);
+        let deserialized = Bitmap::deserialize::<Portable>(&buffer);
+
+        prop_assert_eq!(&original , &deserialized);
+
+        let deserialized = unsafe { Bitmap::deserialize_unchecked(&buffer) };
 
         prop_assert_eq!(original , deserialized);
     }
 
+    #[test]
+    fn frozen_view_matches_bitmap(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        other_indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        start in 0u32..1_000_000,
+        len in 0u32..100_000,
+        optimize in proptest::bool::ANY
+    ) {
+        let mut bitmap = Bitmap::of(&indices);
+        bitmap.add_range(start..start + len);
+        if optimize {
+            bitmap.run_optimize();
+        }
+        let other = Bitmap::of(&other_indices);
+
+        // Lay the data out in an aligned region of a larger buffer, as it would be in a mapped file
+        let serialized = bitmap.serialize::<Frozen>();
+        let mut buffer = vec![0u8; serialized.len() + 64];
+        let offset = buffer.as_ptr().align_offset(32) + 32;
+        buffer[offset..offset + serialized.len()].copy_from_slice(&serialized);
+        let data = &buffer[offset..offset + serialized.len()];
+
+        let view = FrozenBitmapView::try_deserialize(data).unwrap();
+        prop_assert_eq!(view.cardinality(), bitmap.cardinality());
+        prop_assert_eq!(view.minimum(), bitmap.minimum());
+        prop_assert_eq!(view.maximum(), bitmap.maximum());
+        for &index in indices.iter().chain(&other_indices) {
+            prop_assert_eq!(view.contains(index), bitmap.contains(index));
+        }
+        prop_assert!(view.iter().eq(bitmap.iter()));
+        prop_assert!(view.iter().rev().eq(bitmap.iter().rev()));
+        prop_assert_eq!(view.and_cardinality(&other), bitmap.and_cardinality(&other));
+        prop_assert_eq!(view.or_cardinality(&other), bitmap.or_cardinality(&other));
+        prop_assert_eq!(view.xor_cardinality(&other), bitmap.xor_cardinality(&other));
+        prop_assert_eq!(view.andnot_cardinality(&other), bitmap.andnot_cardinality(&other));
+        prop_assert_eq!(view.and(&other), bitmap.and(&other));
+        prop_assert_eq!(view.serialize::<Portable>(), bitmap.serialize::<Portable>());
+
+        let owned: Bitmap = view.clone();
+        drop(view);
+        prop_assert_eq!(owned, bitmap);
+
+        prop_assert!(FrozenBitmapView::try_deserialize(&buffer[offset - 1..offset - 1 + serialized.len()]).is_none());
+    }
+
+    #[test]
+    fn frozen_serialize_into_roundtrip(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        start in 0u32..1_000_000,
+        len in 0u32..100_000,
+        prefix in 0usize..64
+    ) {
+        let mut bitmap = Bitmap::of(&indices);
+        bitmap.add_range(start..start + len);
+
+        // Leave stale bytes in the buffer, they must be cleared rather than shift the alignment
+        let mut buffer = vec![0xff; prefix];
+        let serialized = bitmap.serialize_frozen_into(&mut buffer);
+        prop_assert_eq!(serialized.as_ptr().align_offset(32), 0);
+        prop_assert_eq!(serialized, &bitmap.serialize::<Frozen>()[..]);
+
+        let view = FrozenBitmapView::try_from(serialized).unwrap();
+        prop_assert_eq!(&*view, &bitmap);
+        drop(view);
+
+        prop_assert_eq!(
+            FrozenBitmapView::try_from(&serialized[1..]).unwrap_err(),
+            FrozenViewError::Misaligned
+        );
+        prop_assert_eq!(
+            FrozenBitmapView::try_from(&serialized[..serialized.len() - 1]).unwrap_err(),
+            FrozenViewError::Invalid
+        );
+    }
+
+    #[test]
+    fn test_bitmap_serialization_formats_roundtrip(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        start in 0u32..1_000_000,
+        len in 0u32..100_000,
+        optimize in proptest::bool::ANY
+    ) {
+        let mut original = Bitmap::of(&indices);
+        original.add_range(start..start + len);
+        if optimize {
+            original.run_optimize();
+        }
+
+        let portable = original.serialize::<Portable>();
+        prop_assert_eq!(portable.len(), original.get_serialized_size_in_bytes());
+        prop_assert_eq!(Bitmap::try_deserialize::<Portable>(&portable), Some(original.clone()));
+        prop_assert_eq!(Bitmap::try_deserialize::<Native>(&portable), None);
+
+        let native = original.serialize::<Native>();
+        prop_assert_eq!(native.len(), <Native as Serializer>::get_serialized_size_in_bytes(&original));
+        prop_assert_eq!(Bitmap::try_deserialize::<Native>(&native), Some(original.clone()));
+        prop_assert_eq!(Bitmap::try_deserialize::<Portable>(&native), None);
+
+        let frozen = original.serialize::<Frozen>();
+        prop_assert_eq!(frozen.len(), original.get_frozen_serialized_size_in_bytes());
+        prop_assert_eq!(Bitmap::try_deserialize::<Frozen>(&frozen), Some(original));
+    }
+
     #[test]
     fn test_treemap_native_serialization_roundtrip(
         indices in prop::collection::vec(proptest::num::u64::ANY, 1..3000)
@@ -189,6 +2197,26 @@ proptest! {
         prop_assert_eq!(original , deserialized);
     }
 
+    #[test]
+    fn test_treemap_portable_serialization_roundtrip(
+        indices in prop::collection::vec(proptest::num::u64::ANY, 0..3000)
+    ) {
+        use croaring::treemap::PortableSerializer;
+
+        let original = Treemap::of(&indices);
+
+        let buffer = original.serialize().unwrap();
+        prop_assert_eq!(buffer.len(), original.get_serialized_size_in_bytes());
+
+        let mut written = vec![0xFF];
+        original.serialize_into(&mut written).unwrap();
+        prop_assert_eq!(&written[1..], &buffer[..]);
+
+        let deserialized = Treemap::deserialize(&buffer).unwrap();
+
+        prop_assert_eq!(original , deserialized);
+    }
+
     #[test]
     fn test_treemap_jvm_serialization_roundtrip(
         indices in prop::collection::vec(proptest::num::u64::ANY, 1..3000)
@@ -0,0 +1,33 @@
+#![cfg(feature = "rayon")]
+
+use croaring::Bitmap;
+use proptest::prelude::*;
+
+#[test]
+fn par_fast_or_many_bitmaps() {
+    let bitmaps: Vec<Bitmap> = (0..10_000u32)
+        .map(|i| Bitmap::from_range(i * 1_000..i * 1_000 + (i % 700)))
+        .collect();
+    let refs: Vec<&Bitmap> = bitmaps.iter().collect();
+
+    let union = Bitmap::par_fast_or(&bitmaps);
+    assert_eq!(union, Bitmap::fast_or(&refs));
+    assert_eq!(
+        union.cardinality(),
+        (0..10_000u64).map(|i| i % 700).sum::<u64>()
+    );
+}
+
+proptest! {
+    #[test]
+    fn par_fast_or_matches_fast_or(
+        bitmaps in prop::collection::vec(
+            prop::collection::vec(proptest::num::u32::ANY, 0..50)
+                .prop_map(|values| Bitmap::of(&values)),
+            0..300,
+        )
+    ) {
+        let refs: Vec<&Bitmap> = bitmaps.iter().collect();
+        prop_assert_eq!(Bitmap::par_fast_or(&bitmaps), Bitmap::fast_or(&refs));
+    }
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "serde")]
+
+use croaring::{Bitmap, Portable};
+use proptest::prelude::*;
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
+
+#[test]
+fn serde_json_rejects_invalid_input() {
+    assert!(serde_json::from_str::<Bitmap>("\"not base64!\"").is_err());
+    assert!(serde_json::from_str::<Bitmap>("\"AQID\"").is_err());
+    assert!(serde_json::from_str::<Bitmap>("[1, 2, 3]").is_err());
+}
+
+#[test]
+fn bincode_rejects_invalid_input() {
+    let buffer = bincode::serialize(&vec![3u8, 1, 2]).unwrap();
+    assert!(bincode::deserialize::<Bitmap>(&buffer).is_err());
+}
+
+/// A sequence of bytes which claims to be far longer than it is
+struct ForgedLengthBytes(std::vec::IntoIter<u8>);
+
+impl<'de> serde::de::SeqAccess<'de> for ForgedLengthBytes {
+    type Error = serde::de::value::Error;
+
+    fn next_element_seed<T: serde::de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        match self.0.next() {
+            Some(byte) => seed.deserialize(byte.into_deserializer()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(usize::MAX)
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for ForgedLengthBytes {
+    type Error = serde::de::value::Error;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+#[test]
+fn deserialize_rejects_forged_sequence_length() {
+    let valid = Bitmap::of(&[1, 2, 3]).serialize::<Portable>();
+    let deserialized = <Bitmap as Deserialize>::deserialize(ForgedLengthBytes(valid.into_iter()));
+    assert_eq!(deserialized.unwrap(), Bitmap::of(&[1, 2, 3]));
+
+    let invalid =
+        <Bitmap as Deserialize>::deserialize(ForgedLengthBytes(vec![3, 1, 2].into_iter()));
+    assert!(invalid.is_err());
+}
+
+proptest! {
+    #[test]
+    fn test_bitmap_serde_json_roundtrip(
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        optimize in proptest::bool::ANY
+    ) {
+        let mut original = Bitmap::of(&indices);
+        if optimize {
+            original.run_optimize();
+        }
+
+        let json = serde_json::to_string(&original).unwrap();
+        let deserialized: Bitmap = serde_json::from_str(&json).unwrap();
+
+        prop_assert_eq!(original, deserialized);
+    }
+
+    #[test]
+    fn test_bitmap_bincode_roundtrip(
+        start in 0u32..1_000_000,
+        len in 0u32..100_000,
+        indices in prop::collection::vec(proptest::num::u32::ANY, 0..3000),
+        optimize in proptest::bool::ANY
+    ) {
+        let mut original = Bitmap::of(&indices);
+        original.add_range(start..start + len);
+        if optimize {
+            original.run_optimize();
+        }
+
+        let buffer = bincode::serialize(&original).unwrap();
+        let deserialized: Bitmap = bincode::deserialize(&buffer).unwrap();
+
+        prop_assert_eq!(original, deserialized);
+    }
+}